@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::error::{AocError, AocResult};
+use crate::examples::Mismatch;
+use crate::registry::Day;
+use crate::solution::{Answer, Part};
+
+pub const ANSWERS_FILE: &str = "answers.txt";
+
+/// Accepted answers for the real inputs, stored as one `day part answer` line each.
+pub struct AnswerBook {
+    path: PathBuf,
+    answers: BTreeMap<(u8, Part), Answer>,
+}
+
+fn parse_entry(line: &str) -> AocResult<((u8, Part), Answer)> {
+    let mut fields = line.splitn(3, ' ');
+    let mut next_field = || {
+        fields
+            .next()
+            .ok_or(AocError::InvalidFormat(line.to_string()))
+    };
+    let day = next_field()?;
+    let day = u8::from_str(day).map_err(|_| AocError::UnknownSymbol(day.to_string()))?;
+    let part = next_field()?;
+    let part = Part::from_str(part).map_err(|_| AocError::UnknownSymbol(part.to_string()))?;
+    let answer = Answer::parse(next_field()?.trim());
+    Ok(((day, part), answer))
+}
+
+impl AnswerBook {
+    /// A missing file is an empty book.
+    pub fn load<P>(path: P) -> AocResult<Self>
+    where
+        P: Into<PathBuf>,
+    {
+        let path = path.into();
+        let answers = match fs::read_to_string(&path) {
+            Ok(answers) => answers
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(i, line)| parse_entry(line).map_err(|e| AocError::at_line(i + 1, e)))
+                .collect::<AocResult<_>>()?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(AnswerBook { path, answers })
+    }
+
+    pub fn parts(&self, day: u8) -> Vec<Part> {
+        [Part::A, Part::B]
+            .into_iter()
+            .filter(|part| self.answers.contains_key(&(day, *part)))
+            .collect()
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<&Answer> {
+        self.answers.get(&(day, part))
+    }
+
+    pub fn insert(&mut self, day: u8, part: Part, answer: Answer) {
+        self.answers.insert((day, part), answer);
+    }
+
+    pub fn save(&self) -> AocResult<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let answers = self
+            .answers
+            .iter()
+            .map(|((day, part), answer)| format!("{} {} {}\n", day, part, answer))
+            .collect::<String>();
+        fs::write(&self.path, answers)?;
+        Ok(())
+    }
+
+    /// Runs `day` on `input` and diffs every part that has a recorded answer.
+    pub fn check(&self, day: &Day, input: &Path) -> AocResult<Vec<Mismatch>> {
+        let parts = self.parts(day.day);
+        if parts.is_empty() {
+            return Ok(vec![]);
+        }
+        let actual = day.run(&parts, input)?;
+        Ok(parts
+            .into_iter()
+            .zip(actual)
+            .filter_map(|(part, actual)| {
+                let expected = self.get(day.day, part)?;
+                (expected != &actual).then(|| Mismatch {
+                    day: day.day,
+                    part,
+                    expected: expected.clone(),
+                    actual,
+                })
+            })
+            .collect())
+    }
+
+    /// Runs the parts of `day` without a recorded answer on `input` and stores what they answer,
+    /// call `save` afterwards. Recorded answers are never replaced, `check` compares them instead.
+    pub fn record(&mut self, day: &Day, input: &Path) -> AocResult<Vec<(Part, Answer)>> {
+        let parts = [Part::A, Part::B]
+            .into_iter()
+            .filter(|part| self.get(day.day, *part).is_none())
+            .collect::<Vec<_>>();
+        if parts.is_empty() {
+            return Ok(vec![]);
+        }
+        let answers = parts
+            .iter()
+            .copied()
+            .zip(day.run(&parts, input)?)
+            .collect::<Vec<_>>();
+        for (part, answer) in &answers {
+            self.insert(day.day, *part, answer.clone());
+        }
+        Ok(answers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inputs::tests::temp_dir;
+    use crate::registry;
+
+    #[test]
+    fn real_answers_match() {
+        let data = Path::new("data");
+        let book = AnswerBook::load(data.join(ANSWERS_FILE)).unwrap();
+        for day in registry::all_days().iter().filter(|day| day.day <= 6) {
+            assert_eq!(book.parts(day.day), vec![Part::A, Part::B]);
+            let input = data.join(format!("day{}.txt", day.day));
+            assert_eq!(book.check(day, &input).unwrap(), vec![], "day {}", day.day);
+        }
+    }
+
+    #[test]
+    fn record_round_trips() {
+        let path = temp_dir("answers").join(ANSWERS_FILE);
+        let mut book = AnswerBook::load(&path).unwrap();
+        let day = registry::get_day(4).unwrap();
+        let fixture = Path::new("data/day4t.txt");
+        book.record(day, fixture).unwrap();
+        book.insert(4, Part::B, Answer::Number(5));
+        assert_eq!(book.record(day, fixture).unwrap(), vec![]);
+        book.save().unwrap();
+        let book = AnswerBook::load(&path).unwrap();
+        assert_eq!(book.get(4, Part::A), Some(&Answer::Number(2)));
+        assert_eq!(
+            book.check(day, fixture).unwrap(),
+            vec![Mismatch {
+                day: 4,
+                part: Part::B,
+                expected: Answer::Number(5),
+                actual: Answer::Number(4),
+            }]
+        );
+    }
+}
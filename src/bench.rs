@@ -0,0 +1,144 @@
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+
+use crate::error::AocResult;
+use crate::solution::Solution;
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub max: Duration,
+    pub mean: Duration,
+    pub median: Duration,
+    pub std_dev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        if samples.is_empty() {
+            return Stats::default();
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let runs = sorted.len();
+        let mean = sorted.iter().sum::<Duration>() / runs as u32;
+        let variance = sorted
+            .iter()
+            .map(|sample| (sample.as_secs_f64() - mean.as_secs_f64()).powi(2))
+            .sum::<f64>()
+            / runs as f64;
+        let median = if runs.is_multiple_of(2) {
+            (sorted[runs / 2 - 1] + sorted[runs / 2]) / 2
+        } else {
+            sorted[runs / 2]
+        };
+        Stats {
+            runs,
+            min: sorted[0],
+            max: sorted[runs - 1],
+            mean,
+            median,
+            std_dev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let cell = format!("{:.1?} ± {:.1?}", self.mean, self.std_dev);
+        match f.width() {
+            Some(width) => write!(f, "{:<width$}", cell, width = width),
+            None => write!(f, "{}", cell),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Timings {
+    pub parse: Stats,
+    pub part_one: Stats,
+    pub part_two: Stats,
+}
+
+impl Timings {
+    pub fn total_mean(&self) -> Duration {
+        self.parse.mean + self.part_one.mean + self.part_two.mean
+    }
+}
+
+fn timed<T, F>(samples: &mut Vec<Duration>, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let start = Instant::now();
+    let result = f();
+    samples.push(start.elapsed());
+    result
+}
+
+/// Parses `input` and answers both parts `runs` times, timing every phase separately.
+pub fn measure<S>(input: &str, runs: usize) -> AocResult<Timings>
+where
+    S: Solution,
+{
+    let mut parse = Vec::with_capacity(runs);
+    let mut part_one = Vec::with_capacity(runs);
+    let mut part_two = Vec::with_capacity(runs);
+    for _ in 0..runs {
+        let solution = timed(&mut parse, || S::parse_str(input))?;
+        timed(&mut part_one, || solution.part_one())?;
+        timed(&mut part_two, || solution.part_two())?;
+    }
+    Ok(Timings {
+        parse: Stats::from_samples(&parse),
+        part_one: Stats::from_samples(&part_one),
+        part_two: Stats::from_samples(&part_two),
+    })
+}
+
+const COLUMN: usize = 24;
+
+pub fn table_header() -> String {
+    format!(
+        "{:<4} {:<12} {:<COLUMN$} {:<COLUMN$} {:<COLUMN$} {}",
+        "day", "variant", "parse", "part one", "part two", "runs"
+    )
+}
+
+pub fn table_row(day: u8, variant: &str, timings: &Timings) -> String {
+    format!(
+        "{:<4} {:<12} {:<COLUMN$} {:<COLUMN$} {:<COLUMN$} {}",
+        day, variant, timings.parse, timings.part_one, timings.part_two, timings.parse.runs
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day4::Day4;
+    use crate::execution::Execution;
+
+    #[test]
+    fn stats_from_samples() {
+        let samples = [4, 1, 3, 2].map(Duration::from_millis);
+        let stats = Stats::from_samples(&samples);
+        assert_eq!(stats.runs, 4);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.max, Duration::from_millis(4));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.mean, Duration::from_micros(2500));
+        assert_eq!(stats.std_dev.as_micros(), 1118);
+    }
+
+    #[test]
+    fn measure_times_every_phase() {
+        let input = "2-4,6-8\n2-3,4-5\n5-7,7-9\n";
+        let timings = Execution::Sequential
+            .install(|| measure::<Day4>(input, 3))
+            .unwrap();
+        assert_eq!(timings.parse.runs, 3);
+        assert_eq!(timings.part_one.runs, 3);
+        assert_eq!(timings.part_two.runs, 3);
+    }
+}
@@ -0,0 +1,733 @@
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::answers::{AnswerBook, ANSWERS_FILE};
+use crate::bench;
+use crate::day1::Day1;
+use crate::day3::{Day3, Packing};
+use crate::error::{AocError, AocResult};
+use crate::examples;
+use crate::execution::Execution;
+use crate::inputs::{InputCache, UreqClient};
+use crate::registry::{self, Day, RegistryError};
+use crate::scaffold::Scaffold;
+use crate::solution::{Answer, Part, Solution, UnknownPart};
+use crate::submit::{History, Submitter};
+use crate::utils;
+
+const USAGE: &str = "Usage:
+    aoc2022 run (--day N | --all) [--part a|b] [--input PATH|-] [--cache-dir DIR]
+        [--execution sequential|rayon|THREADS] [--stream | --time [--runs N] [--compare]]
+        [--top N (day 1 only)]
+        [--group-size N] [--compartments N] [--breakdown] [--validate] (day 3 only)
+    aoc2022 check [--day N] [--cache-dir DIR] [--record]
+    aoc2022 example --day N --html PATH [--data-dir DIR]
+    aoc2022 submit --day N --part a|b [--answer ANSWER] [--cache-dir DIR]
+    aoc2022 generate --day N [--seed N] [--size N] [--output PATH]
+    aoc2022 new --day N";
+
+#[derive(Error, Debug)]
+pub enum CliError {
+    #[error("{USAGE}")]
+    Usage,
+
+    #[error("Unknown argument {0}\n{USAGE}")]
+    UnknownArgument(String),
+
+    #[error("Missing value for {0}")]
+    MissingValue(String),
+
+    #[error("Invalid day {0}")]
+    InvalidDay(String),
+
+    #[error("Invalid number {0}")]
+    InvalidNumber(String),
+
+    #[error("Invalid execution {0}, expected sequential, rayon or a thread count")]
+    InvalidExecution(String),
+
+    #[error("--top only works with --day 1")]
+    TopNeedsDay1,
+
+    #[error("--top can not be combined with --stream, --time or --part")]
+    TopWithOptions,
+
+    #[error("--group-size, --compartments, --breakdown and --validate only work with --day 3")]
+    NeedsDay3,
+
+    #[error("--stream can not be combined with --time or day 3 options")]
+    StreamWithTime,
+
+    #[error("{0} rucksack(s) or group(s) do not share exactly one item")]
+    InvalidRucksacks(usize),
+
+    #[error("{0} answer(s) differ from {ANSWERS_FILE}")]
+    Regressions(usize),
+
+    #[error("--input can not be combined with --all")]
+    InputWithAll,
+
+    #[error(transparent)]
+    UnknownPart(#[from] UnknownPart),
+
+    #[error(transparent)]
+    Registry(#[from] RegistryError),
+
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    #[error(transparent)]
+    Solve(#[from] AocError),
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Selection {
+    Day(u8),
+    All,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct RunArgs {
+    pub selection: Selection,
+    pub part: Option<Part>,
+    pub input: Option<PathBuf>,
+    pub cache_dir: Option<PathBuf>,
+    pub execution: Execution,
+    pub stream: bool,
+    pub top: Option<usize>,
+    pub packing: Option<Packing>,
+    pub breakdown: bool,
+    pub validate: bool,
+    pub time: Option<Timing>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Timing {
+    pub runs: usize,
+    pub compare: bool,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct CheckArgs {
+    pub selection: Selection,
+    pub cache_dir: Option<PathBuf>,
+    pub record: bool,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ExampleArgs {
+    pub day: u8,
+    pub html: PathBuf,
+    pub data_dir: PathBuf,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct SubmitArgs {
+    pub day: u8,
+    pub part: Part,
+    pub answer: Option<Answer>,
+    pub cache_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct GenerateArgs {
+    pub day: u8,
+    pub seed: u64,
+    pub size: usize,
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Command {
+    Run(RunArgs),
+    Check(CheckArgs),
+    Example(ExampleArgs),
+    Submit(SubmitArgs),
+    Generate(GenerateArgs),
+    New(u8),
+}
+
+fn next_value<I>(args: &mut I, flag: &str) -> Result<String, CliError>
+where
+    I: Iterator<Item = String>,
+{
+    args.next().ok_or(CliError::MissingValue(flag.to_string()))
+}
+
+fn parse_day(value: String) -> Result<u8, CliError> {
+    u8::from_str(&value).map_err(|_| CliError::InvalidDay(value))
+}
+
+fn parse_number(value: String) -> Result<usize, CliError> {
+    usize::from_str(&value).map_err(|_| CliError::InvalidNumber(value))
+}
+
+fn parse_run<I>(mut args: I) -> Result<RunArgs, CliError>
+where
+    I: Iterator<Item = String>,
+{
+    let mut selection = None;
+    let mut part = None;
+    let mut input = None;
+    let mut cache_dir = None;
+    let mut execution = Execution::default();
+    let mut stream = false;
+    let mut top = None;
+    let mut group_size = None;
+    let mut compartments = None;
+    let mut breakdown = false;
+    let mut validate = false;
+    let mut time = false;
+    let mut runs = 10;
+    let mut compare = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" | "-d" => {
+                selection = Some(Selection::Day(parse_day(next_value(&mut args, &arg)?)?))
+            }
+            "--execution" | "-e" => {
+                let value = next_value(&mut args, &arg)?;
+                execution =
+                    Execution::from_str(&value).map_err(|_| CliError::InvalidExecution(value))?
+            }
+            "--sequential" => execution = Execution::Sequential,
+            "--stream" => stream = true,
+            "--top" => top = Some(parse_number(next_value(&mut args, &arg)?)?),
+            "--group-size" => group_size = Some(parse_number(next_value(&mut args, &arg)?)?),
+            "--compartments" => compartments = Some(parse_number(next_value(&mut args, &arg)?)?),
+            "--breakdown" => breakdown = true,
+            "--validate" => validate = true,
+            "--time" => time = true,
+            "--runs" => {
+                runs = match parse_number(next_value(&mut args, &arg)?)? {
+                    0 => return Err(CliError::InvalidNumber("0".to_string())),
+                    runs => runs,
+                }
+            }
+            "--compare" => compare = true,
+            "--all" => selection = Some(Selection::All),
+            "--part" | "-p" => part = Some(Part::from_str(&next_value(&mut args, &arg)?)?),
+            "--input" | "-i" => input = Some(PathBuf::from(next_value(&mut args, &arg)?)),
+            "--cache-dir" => cache_dir = Some(PathBuf::from(next_value(&mut args, &arg)?)),
+            _ => return Err(CliError::UnknownArgument(arg)),
+        }
+    }
+    let selection = selection.ok_or(CliError::Usage)?;
+    if selection == Selection::All && input.is_some() {
+        return Err(CliError::InputWithAll);
+    }
+    if top.is_some() && selection != Selection::Day(1) {
+        return Err(CliError::TopNeedsDay1);
+    }
+    if top.is_some() && (stream || time || compare || part.is_some()) {
+        return Err(CliError::TopWithOptions);
+    }
+    let day3_only = group_size.is_some() || compartments.is_some() || breakdown || validate;
+    if day3_only && selection != Selection::Day(3) {
+        return Err(CliError::NeedsDay3);
+    }
+    let packing = match (group_size, compartments) {
+        (None, None) => None,
+        _ => {
+            let default = Packing::default();
+            Some(Packing::new(
+                group_size.unwrap_or(default.group_size()),
+                compartments.unwrap_or(default.compartments()),
+            )?)
+        }
+    };
+    if stream && (time || compare || day3_only) {
+        return Err(CliError::StreamWithTime);
+    }
+    Ok(RunArgs {
+        selection,
+        part,
+        input,
+        cache_dir,
+        execution,
+        stream,
+        top,
+        packing,
+        breakdown,
+        validate,
+        time: (time || compare).then_some(Timing { runs, compare }),
+    })
+}
+
+fn parse_check<I>(mut args: I) -> Result<CheckArgs, CliError>
+where
+    I: Iterator<Item = String>,
+{
+    let mut selection = Selection::All;
+    let mut cache_dir = None;
+    let mut record = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" | "-d" => selection = Selection::Day(parse_day(next_value(&mut args, &arg)?)?),
+            "--cache-dir" => cache_dir = Some(PathBuf::from(next_value(&mut args, &arg)?)),
+            "--record" => record = true,
+            _ => return Err(CliError::UnknownArgument(arg)),
+        }
+    }
+    Ok(CheckArgs {
+        selection,
+        cache_dir,
+        record,
+    })
+}
+
+fn parse_example<I>(mut args: I) -> Result<ExampleArgs, CliError>
+where
+    I: Iterator<Item = String>,
+{
+    let mut day = None;
+    let mut html = None;
+    let mut data_dir = PathBuf::from("data");
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" | "-d" => day = Some(parse_day(next_value(&mut args, &arg)?)?),
+            "--html" => html = Some(PathBuf::from(next_value(&mut args, &arg)?)),
+            "--data-dir" => data_dir = PathBuf::from(next_value(&mut args, &arg)?),
+            _ => return Err(CliError::UnknownArgument(arg)),
+        }
+    }
+    Ok(ExampleArgs {
+        day: day.ok_or(CliError::Usage)?,
+        html: html.ok_or(CliError::Usage)?,
+        data_dir,
+    })
+}
+
+fn parse_submit<I>(mut args: I) -> Result<SubmitArgs, CliError>
+where
+    I: Iterator<Item = String>,
+{
+    let mut day = None;
+    let mut part = None;
+    let mut answer = None;
+    let mut cache_dir = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" | "-d" => day = Some(parse_day(next_value(&mut args, &arg)?)?),
+            "--part" | "-p" => part = Some(Part::from_str(&next_value(&mut args, &arg)?)?),
+            "--answer" => answer = Some(Answer::parse(&next_value(&mut args, &arg)?)),
+            "--cache-dir" => cache_dir = Some(PathBuf::from(next_value(&mut args, &arg)?)),
+            _ => return Err(CliError::UnknownArgument(arg)),
+        }
+    }
+    Ok(SubmitArgs {
+        day: day.ok_or(CliError::Usage)?,
+        part: part.ok_or(CliError::Usage)?,
+        answer,
+        cache_dir,
+    })
+}
+
+fn parse_generate<I>(mut args: I) -> Result<GenerateArgs, CliError>
+where
+    I: Iterator<Item = String>,
+{
+    let mut day = None;
+    let mut seed = 0;
+    let mut size = 1000;
+    let mut output = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" | "-d" => day = Some(parse_day(next_value(&mut args, &arg)?)?),
+            "--seed" => seed = parse_number(next_value(&mut args, &arg)?)? as u64,
+            "--size" => size = parse_number(next_value(&mut args, &arg)?)?,
+            "--output" | "-o" => output = Some(PathBuf::from(next_value(&mut args, &arg)?)),
+            _ => return Err(CliError::UnknownArgument(arg)),
+        }
+    }
+    Ok(GenerateArgs {
+        day: day.ok_or(CliError::Usage)?,
+        seed,
+        size,
+        output,
+    })
+}
+
+fn parse_new<I>(mut args: I) -> Result<u8, CliError>
+where
+    I: Iterator<Item = String>,
+{
+    let mut day = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" | "-d" => day = Some(parse_day(next_value(&mut args, &arg)?)?),
+            _ => return Err(CliError::UnknownArgument(arg)),
+        }
+    }
+    day.ok_or(CliError::Usage)
+}
+
+pub fn parse_args<I>(mut args: I) -> Result<Command, CliError>
+where
+    I: Iterator<Item = String>,
+{
+    match args.next().as_deref() {
+        Some("run") => Ok(Command::Run(parse_run(args)?)),
+        Some("check") => Ok(Command::Check(parse_check(args)?)),
+        Some("example") => Ok(Command::Example(parse_example(args)?)),
+        Some("submit") => Ok(Command::Submit(parse_submit(args)?)),
+        Some("generate") => Ok(Command::Generate(parse_generate(args)?)),
+        Some("new") => Ok(Command::New(parse_new(args)?)),
+        Some(other) => Err(CliError::UnknownArgument(other.to_string())),
+        None => Err(CliError::Usage),
+    }
+}
+
+fn input_cache(cache_dir: &Option<PathBuf>) -> InputCache<UreqClient> {
+    match cache_dir {
+        Some(cache_dir) => InputCache::from_env().with_dir(cache_dir),
+        None => InputCache::from_env(),
+    }
+}
+
+fn run_days(args: RunArgs) -> Result<(), CliError> {
+    let cache = input_cache(&args.cache_dir);
+    let days = match args.selection {
+        Selection::Day(day) => vec![registry::get_day(day)?],
+        Selection::All => registry::all_days().iter().collect(),
+    };
+    let parts = match args.part {
+        Some(part) => vec![part],
+        None => vec![Part::A, Part::B],
+    };
+    if args.time.is_some() {
+        println!("{}", bench::table_header());
+    }
+    for day in days {
+        if args.stream {
+            let answers = args.execution.install(|| match &args.input {
+                Some(input) if input.as_os_str() == "-" => {
+                    day.stream_reader(&parts, &mut io::stdin().lock())
+                }
+                Some(input) => day.stream(&parts, input),
+                None => day.stream(&parts, &cache.resolve(day.day)?),
+            })?;
+            print_answers(day, &parts, answers);
+            continue;
+        }
+        let input = match &args.input {
+            Some(input) if input.as_os_str() == "-" => utils::read_input_string(io::stdin())?,
+            Some(input) => utils::get_input_string(input)?,
+            None => utils::get_input_string(cache.resolve(day.day)?)?,
+        };
+        if let Some(n) = args.top {
+            args.execution
+                .install(|| print_top(&Day1::parse_str(&input)?, n))?;
+            continue;
+        }
+        if args.packing.is_some() || args.breakdown || args.validate {
+            run_day3(day, &input, &parts, &args)?;
+            continue;
+        }
+        match &args.time {
+            Some(timing) => time_day(day, &input, args.execution, timing)?,
+            None => {
+                let answers = args
+                    .execution
+                    .install(|| day.run_reader(&parts, &mut input.as_bytes()))?;
+                print_answers(day, &parts, answers);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_top(day: &Day1, n: usize) -> AocResult<()> {
+    let inventory = day.inventory();
+    let top = inventory.top(n);
+    for (rank, (elf, calories)) in top.iter().enumerate() {
+        println!("#{:<3} elf {:<5} {}", rank + 1, elf + 1, calories);
+    }
+    println!(
+        "Top {} total: {}",
+        n,
+        top.iter().map(|(_, calories)| calories).sum::<u64>()
+    );
+    if let (Some(mean), Some(median)) = (inventory.mean(), inventory.median()) {
+        println!(
+            "{} elves, mean {:.1}, median {:.1}, p90 {}",
+            inventory.totals().len(),
+            mean,
+            median,
+            inventory.percentile(90.0).unwrap_or(0)
+        );
+    }
+    Ok(())
+}
+
+/// Runs day 3 with its own packing, printing the shared items of every line and the lines that
+/// break the one shared item rule when asked to.
+fn run_day3(day: &Day, input: &str, parts: &[Part], args: &RunArgs) -> Result<(), CliError> {
+    let day3 = Day3::parse_str(input)?.with_packing(args.packing.unwrap_or_default());
+    let (answers, invalid) = args.execution.install(|| {
+        let mut invalid = 0;
+        for &part in parts {
+            if args.breakdown {
+                println!("Part {}: line, shared item, priority", part);
+                for shared in day3.shared_items(part)? {
+                    println!("{}, {}, {}", shared.line, shared.items, shared.priority());
+                }
+            }
+            if args.validate {
+                for diagnostic in day3.validate(part)? {
+                    println!("Part {}: {}", part, diagnostic);
+                    invalid += 1;
+                }
+            }
+        }
+        let answers = parts
+            .iter()
+            .map(|&part| day3.part(part))
+            .collect::<AocResult<Vec<_>>>()?;
+        Ok((answers, invalid))
+    })?;
+    print_answers(day, parts, answers);
+    match invalid {
+        0 => Ok(()),
+        count => Err(CliError::InvalidRucksacks(count)),
+    }
+}
+
+fn print_answers(day: &Day, parts: &[Part], answers: Vec<Answer>) {
+    for (part, answer) in parts.iter().zip(answers) {
+        println!("Day {} part {}: {}", day.day, part, answer);
+    }
+}
+
+fn time_day(day: &Day, input: &str, execution: Execution, timing: &Timing) -> Result<(), CliError> {
+    let parallel = execution.install(|| day.time(input, timing.runs))?;
+    println!(
+        "{}",
+        bench::table_row(day.day, &execution.to_string(), &parallel)
+    );
+    if timing.compare {
+        let sequential = Execution::Sequential.install(|| day.time(input, timing.runs))?;
+        println!("{}", bench::table_row(day.day, "sequential", &sequential));
+        println!(
+            "{:<4} {:<12} {:.2}x",
+            day.day,
+            "speedup",
+            sequential.total_mean().as_secs_f64() / parallel.total_mean().as_secs_f64()
+        );
+    }
+    Ok(())
+}
+
+fn check_answers(args: CheckArgs) -> Result<(), CliError> {
+    let cache = input_cache(&args.cache_dir);
+    let mut book = AnswerBook::load(cache.dir().join(ANSWERS_FILE))?;
+    let days = match args.selection {
+        Selection::Day(day) => vec![registry::get_day(day)?],
+        Selection::All => registry::all_days().iter().collect(),
+    };
+    let mut regressions = 0;
+    for day in days {
+        if args.record {
+            for (part, answer) in book.record(day, &cache.resolve(day.day)?)? {
+                println!("Day {} part {}: recorded {}", day.day, part, answer);
+            }
+        }
+        if book.parts(day.day).is_empty() {
+            println!("Day {}: no recorded answers", day.day);
+            continue;
+        }
+        let mismatches = book.check(day, &cache.resolve(day.day)?)?;
+        for part in book.parts(day.day) {
+            match mismatches.iter().find(|mismatch| mismatch.part == part) {
+                Some(mismatch) => println!(
+                    "Day {} part {}: expected {}, got {}",
+                    day.day, part, mismatch.expected, mismatch.actual
+                ),
+                None => println!("Day {} part {}: ok", day.day, part),
+            }
+        }
+        regressions += mismatches.len();
+    }
+    if args.record {
+        book.save()?;
+    }
+    match regressions {
+        0 => Ok(()),
+        count => Err(CliError::Regressions(count)),
+    }
+}
+
+fn extract_example(args: ExampleArgs) -> Result<(), CliError> {
+    let html = std::fs::read_to_string(&args.html)?;
+    let example = examples::extract_example(&html)?;
+    examples::write_fixture(&args.data_dir, args.day, &example)?;
+    println!(
+        "Wrote {} with {} expected answer(s)",
+        examples::fixture_path(&args.data_dir, args.day).display(),
+        example.answers.len()
+    );
+    Ok(())
+}
+
+fn submit_answer(args: SubmitArgs) -> Result<(), CliError> {
+    let cache = input_cache(&args.cache_dir);
+    let answer = match args.answer {
+        Some(answer) => answer,
+        None => {
+            let day = registry::get_day(args.day)?;
+            day.run(&[args.part], &cache.resolve(args.day)?)?.remove(0)
+        }
+    };
+    let history = History::load(cache.dir().join("submissions.txt"))?;
+    let verdict = Submitter::from_env(history).submit(args.day, args.part, answer.clone())?;
+    println!(
+        "Day {} part {}: {} is {}",
+        args.day, args.part, answer, verdict
+    );
+    Ok(())
+}
+
+fn generate_input(args: GenerateArgs) -> Result<(), CliError> {
+    let input = registry::get_day(args.day)?.generate(args.seed, args.size)?;
+    match args.output {
+        Some(output) => std::fs::write(output, input)?,
+        None => io::stdout().write_all(input.as_bytes())?,
+    }
+    Ok(())
+}
+
+fn new_day(day: u8) -> Result<(), CliError> {
+    for path in Scaffold::new(".").create_day(day)? {
+        println!("Created {}", path.display());
+    }
+    Ok(())
+}
+
+pub fn run<I>(args: I) -> Result<(), CliError>
+where
+    I: Iterator<Item = String>,
+{
+    match parse_args(args)? {
+        Command::Run(run_args) => run_days(run_args),
+        Command::Check(check_args) => check_answers(check_args),
+        Command::Example(example_args) => extract_example(example_args),
+        Command::Submit(submit_args) => submit_answer(submit_args),
+        Command::Generate(generate_args) => generate_input(generate_args),
+        Command::New(day) => new_day(day),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> impl Iterator<Item = String> + '_ {
+        line.split_whitespace().map(String::from)
+    }
+
+    #[test]
+    fn parse_run_works() {
+        let result = parse_args(args("run --day 4 --part b --input data/day4t.txt")).unwrap();
+        assert_eq!(
+            result,
+            Command::Run(RunArgs {
+                selection: Selection::Day(4),
+                part: Some(Part::B),
+                input: Some(PathBuf::from("data/day4t.txt")),
+                cache_dir: None,
+                execution: Execution::Rayon,
+                stream: false,
+                top: None,
+                packing: None,
+                breakdown: false,
+                validate: false,
+                time: None,
+            })
+        )
+    }
+
+    #[test]
+    fn parse_run_packing() {
+        let result = parse_args(args("run --day 3 --group-size 4")).unwrap();
+        let Command::Run(run_args) = result else {
+            panic!("expected run");
+        };
+        assert_eq!(run_args.packing, Some(Packing::new(4, 2).unwrap()));
+        assert!(matches!(
+            parse_args(args("run --day 2 --compartments 3")),
+            Err(CliError::NeedsDay3)
+        ));
+        assert!(matches!(
+            parse_args(args("run --day 1 --validate")),
+            Err(CliError::NeedsDay3)
+        ));
+        assert!(matches!(
+            parse_args(args("run --day 3 --breakdown --stream")),
+            Err(CliError::StreamWithTime)
+        ));
+        assert!(matches!(
+            parse_args(args("run --day 3 --group-size 0")),
+            Err(CliError::Solve(AocError::InvalidPacking { .. }))
+        ));
+    }
+
+    #[test]
+    fn parse_generate_works() {
+        let result = parse_args(args("generate --day 3 --seed 7 --size 30")).unwrap();
+        assert_eq!(
+            result,
+            Command::Generate(GenerateArgs {
+                day: 3,
+                seed: 7,
+                size: 30,
+                output: None,
+            })
+        )
+    }
+
+    #[test]
+    fn parse_run_rejects_bad_input() {
+        assert!(matches!(parse_args(args("run")), Err(CliError::Usage)));
+        assert!(matches!(
+            parse_args(args("run --day 1 --time --runs many")),
+            Err(CliError::InvalidNumber(_))
+        ));
+        assert!(matches!(
+            parse_args(args("run --day 1 --time --runs 0")),
+            Err(CliError::InvalidNumber(_))
+        ));
+        assert!(matches!(
+            parse_args(args("run --day 1 --execution 0")),
+            Err(CliError::InvalidExecution(_))
+        ));
+        assert!(matches!(
+            parse_args(args("run --day 1 --stream --time")),
+            Err(CliError::StreamWithTime)
+        ));
+        assert!(matches!(
+            parse_args(args("run --day 2 --top 3")),
+            Err(CliError::TopNeedsDay1)
+        ));
+        for extra in ["--stream", "--time", "--compare", "--part a"] {
+            assert!(matches!(
+                parse_args(args(&format!("run --day 1 --top 2 {extra}"))),
+                Err(CliError::TopWithOptions)
+            ));
+        }
+        assert!(matches!(
+            parse_args(args("run --day x")),
+            Err(CliError::InvalidDay(_))
+        ));
+        assert!(matches!(
+            parse_args(args("run --all --input data/day1.txt")),
+            Err(CliError::InputWithAll)
+        ));
+        assert!(matches!(
+            run(args("run --day 25")),
+            Err(CliError::Registry(RegistryError::NotImplemented(25)))
+        ));
+    }
+}
@@ -1,58 +1,230 @@
-use crate::utils;
+use crate::error::{AocError, AocResult};
+use crate::generate::Generate;
+use crate::solution::{Answer, Solution};
+use crate::streaming::{for_each_group, read_groups, Group, Streaming};
+use rand::Rng;
 use rayon::prelude::*;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io::BufRead;
+use std::mem;
 use std::path::Path;
-use std::{io, mem};
-
-fn get_elves(input: BufReader<File>) -> Vec<Vec<u64>> {
-    let mut elves: Vec<Vec<u64>> = vec![];
-    let mut cur_elf: Vec<u64> = vec![];
-    for line in input.lines() {
-        if let Ok(line) = line {
-            let trimmed_line = line.trim();
-            if trimmed_line.is_empty() {
-                elves.push(mem::take(&mut cur_elf));
-            } else {
-                let calories = line.parse::<u64>().unwrap();
-                cur_elf.push(calories);
+
+fn parse_calories(line_number: usize, line: &str) -> AocResult<u64> {
+    let indent = line.len() - line.trim_start().len();
+    let value = line.trim();
+    value.parse::<u64>().map_err(|e| AocError::Parse {
+        line: line_number,
+        column: value
+            .find(|c: char| !c.is_ascii_digit())
+            .map_or(indent + 1, |i| line[..indent + i].chars().count() + 1),
+        message: format!("expected calories ({})", e),
+        source_line: line.to_string(),
+    })
+}
+
+fn get_elves<R>(input: R) -> AocResult<Vec<Vec<u64>>>
+where
+    R: BufRead,
+{
+    read_groups(input, parse_calories)
+}
+
+/// Keeps the `n` largest totals seen so far in a min-heap of at most `n` entries. Among equal
+/// totals the earlier elf wins.
+pub struct TopElves {
+    n: usize,
+    heap: BinaryHeap<Reverse<(u64, Reverse<usize>)>>,
+}
+
+impl TopElves {
+    pub fn new(n: usize) -> Self {
+        TopElves {
+            n,
+            heap: BinaryHeap::with_capacity(n + 1),
+        }
+    }
+
+    pub fn offer(&mut self, elf: usize, calories: u64) {
+        self.heap.push(Reverse((calories, Reverse(elf))));
+        if self.heap.len() > self.n {
+            self.heap.pop();
+        }
+    }
+
+    /// `(elf index, calories)` pairs, the largest total first.
+    pub fn into_sorted(self) -> Vec<(usize, u64)> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((calories, Reverse(elf)))| (elf, calories))
+            .collect()
+    }
+}
+
+/// Calorie totals of every elf, in input order.
+pub struct Inventory {
+    totals: Vec<u64>,
+}
+
+impl Inventory {
+    pub fn new(totals: Vec<u64>) -> Self {
+        Inventory { totals }
+    }
+
+    pub fn totals(&self) -> &[u64] {
+        &self.totals
+    }
+
+    /// The `n` elves carrying the most, as `(elf index, calories)` with the largest first.
+    pub fn top(&self, n: usize) -> Vec<(usize, u64)> {
+        let mut top = TopElves::new(n);
+        for (elf, calories) in self.totals.iter().enumerate() {
+            top.offer(elf, *calories);
+        }
+        top.into_sorted()
+    }
+
+    pub fn top_sum(&self, n: usize) -> u64 {
+        self.top(n).iter().map(|(_, calories)| calories).sum()
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        if self.totals.is_empty() {
+            return None;
+        }
+        Some(self.totals.iter().sum::<u64>() as f64 / self.totals.len() as f64)
+    }
+
+    pub fn median(&self) -> Option<f64> {
+        let count = self.totals.len();
+        if count == 0 {
+            return None;
+        }
+        let mut totals = self.totals.clone();
+        let (below, upper, _) = totals.select_nth_unstable(count / 2);
+        let upper = *upper as f64;
+        match below.iter().max() {
+            Some(lower) if count.is_multiple_of(2) => Some((*lower as f64 + upper) / 2.0),
+            _ => Some(upper),
+        }
+    }
+
+    /// Nearest-rank percentile, `None` without elves or when `percent` is not within 0..=100.
+    pub fn percentile(&self, percent: f64) -> Option<u64> {
+        if self.totals.is_empty() || !(0.0..=100.0).contains(&percent) {
+            return None;
+        }
+        let rank = ((percent / 100.0 * self.totals.len() as f64).ceil() as usize).max(1);
+        let mut totals = self.totals.clone();
+        Some(*totals.select_nth_unstable(rank - 1).1)
+    }
+}
+
+pub struct Day1 {
+    elves: Vec<Vec<u64>>,
+}
+
+impl Day1 {
+    pub fn inventory(&self) -> Inventory {
+        Inventory::new(
+            self.elves
+                .par_iter()
+                .map(|elf| elf.iter().sum::<u64>())
+                .collect(),
+        )
+    }
+
+    fn max_calories(&self) -> u64 {
+        self.inventory().top_sum(1)
+    }
+
+    fn top_three_calories(&self) -> u64 {
+        self.inventory().top_sum(3)
+    }
+}
+
+impl Solution for Day1 {
+    fn parse<R>(input: R) -> AocResult<Self>
+    where
+        R: BufRead,
+    {
+        Ok(Day1 {
+            elves: get_elves(input)?,
+        })
+    }
+
+    fn part_one(&self) -> AocResult<Answer> {
+        Ok(self.max_calories().into())
+    }
+
+    fn part_two(&self) -> AocResult<Answer> {
+        Ok(self.top_three_calories().into())
+    }
+}
+
+impl Streaming for Day1 {
+    fn stream<R>(input: R) -> AocResult<[Answer; 2]>
+    where
+        R: BufRead,
+    {
+        let mut top = TopElves::new(3);
+        let mut elf = 0;
+        let mut current = 0;
+        for_each_group(input, |event| {
+            match event {
+                Group::Line(line_number, line) => current += parse_calories(line_number, line)?,
+                Group::End => {
+                    top.offer(elf, mem::take(&mut current));
+                    elf += 1;
+                }
             }
+            Ok(())
+        })?;
+        let top = top.into_sorted();
+        let max = top.first().map_or(0, |(_, calories)| *calories);
+        let top_three = top.iter().map(|(_, calories)| calories).sum::<u64>();
+        Ok([max.into(), top_three.into()])
+    }
+}
+
+impl Generate for Day1 {
+    fn generate<R>(rng: &mut R, size: usize) -> String
+    where
+        R: Rng,
+    {
+        let mut elves = vec![];
+        for _ in 0..size.max(1) {
+            let snacks = rng.gen_range(1..=8);
+            elves.push(
+                (0..snacks)
+                    .map(|_| format!("{}\n", rng.gen_range(1000..=60000)))
+                    .collect::<String>(),
+            );
         }
+        elves.join("\n")
     }
-    elves.push(mem::take(&mut cur_elf));
-    elves
 }
 
-pub fn task_a<P>(file: P) -> io::Result<u64>
+pub fn task_a<P>(file: P) -> AocResult<u64>
 where
     P: AsRef<Path>,
 {
-    let input_reader = utils::get_input_file(file)?;
-    let elves = get_elves(input_reader);
-    Ok(elves
-        .par_iter()
-        .map(|elf| elf.into_iter().sum::<u64>())
-        .max()
-        .unwrap_or(0u64))
+    Ok(Day1::parse_file(file)?.max_calories())
 }
 
-pub fn task_b<P>(file: P) -> io::Result<u64>
+pub fn task_b<P>(file: P) -> AocResult<u64>
 where
     P: AsRef<Path>,
 {
-    let input_reader = utils::get_input_file(file)?;
-    let elves = get_elves(input_reader);
-    let mut sorted_elves = elves
-        .par_iter()
-        .map(|elf| elf.into_iter().sum::<u64>())
-        .collect::<Vec<u64>>();
-    sorted_elves.par_sort();
-    Ok(sorted_elves.par_iter().rev().take(3).sum())
+    Ok(Day1::parse_file(file)?.top_three_calories())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
 
     #[test]
     fn test_a_works() {
@@ -65,4 +237,77 @@ mod tests {
         let result = task_b("data/day1t.txt");
         assert_eq!(result.unwrap(), 45000);
     }
+
+    #[test]
+    fn inventory_answers_queries() {
+        let inventory = Day1::parse_file("data/day1t.txt").unwrap().inventory();
+        assert_eq!(inventory.totals(), [6000, 4000, 11000, 24000, 10000]);
+        assert_eq!(inventory.top(2), vec![(3, 24000), (2, 11000)]);
+        assert_eq!(inventory.top_sum(10), 55000);
+        assert_eq!(inventory.mean(), Some(11000.0));
+        assert_eq!(inventory.median(), Some(10000.0));
+        assert_eq!(inventory.percentile(80.0), Some(11000));
+        assert_eq!(inventory.percentile(101.0), None);
+        assert_eq!(
+            Inventory::new(vec![5, 5, 1, 2]).top(2),
+            vec![(0, 5), (1, 5)]
+        );
+        assert_eq!(Inventory::new(vec![1, 4, 2, 3]).median(), Some(2.5));
+    }
+
+    #[test]
+    fn blank_lines_and_crlf_are_tolerated() {
+        let input = "\r\n1000\r\n 2000 \r\n\r\n\r\n3000\r\n\r\n";
+        let day = Day1::parse_str(input).unwrap();
+        assert_eq!(day.inventory().totals(), [3000, 3000]);
+        assert_eq!(
+            Day1::stream(input.as_bytes()).unwrap(),
+            [3000u64.into(), 6000u64.into()]
+        );
+        let error = Day1::parse_str("1000\n\n  20x0\n").err().unwrap();
+        assert!(matches!(
+            error,
+            AocError::Parse {
+                line: 3,
+                column: 5,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn bad_calories_are_reported() {
+        let result = Day1::parse_str("1000\n\n20x0\n");
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "Parse error at line 3, column 3: expected calories (invalid digit found in string)\n\
+             3 | 20x0\n  |   ^"
+        );
+    }
+
+    /// Sorts every total instead of keeping only the largest ones.
+    fn reference(elves: &[Vec<u64>]) -> (u64, u64) {
+        let mut totals = elves
+            .iter()
+            .map(|elf| elf.iter().sum::<u64>())
+            .collect::<Vec<_>>();
+        totals.sort_by(|a, b| b.cmp(a));
+        (totals[0], totals.iter().take(3).sum())
+    }
+
+    proptest! {
+        #[test]
+        fn matches_reference(elves in vec(vec(0..100_000u64, 1..8), 1..30)) {
+            let input = elves
+                .iter()
+                .map(|elf| elf.iter().map(|calories| format!("{}\n", calories)).collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n");
+            let (max, top_three) = reference(&elves);
+            let day = Day1::parse_str(&input).unwrap();
+            prop_assert_eq!(day.part_one().unwrap(), max.into());
+            prop_assert_eq!(day.part_two().unwrap(), top_three.into());
+            prop_assert_eq!(Day1::stream(input.as_bytes()).unwrap(), [max.into(), top_three.into()]);
+        }
+    }
 }
@@ -1,183 +1,485 @@
-use std::io;
+use std::cmp::Ordering;
 use std::io::BufRead;
 use std::path::Path;
-use std::str::FromStr;
 
-use rayon::prelude::*;
-use thiserror::Error;
+use crate::diagnostics::{check_lines, Diagnosed, Mode};
+use crate::error::{AocError, AocResult};
+use crate::generate::Generate;
+use crate::solution::{Answer, Solution};
+use crate::streaming::{for_each_line, Streaming};
+use rand::seq::SliceRandom;
+use rand::Rng;
 
-use crate::utils;
+/// A shape is its position in the `Rules` it belongs to.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Shape(usize);
 
-#[derive(Error, Debug)]
-enum ParsingError {
-    #[error("Unknown symbol {0}")]
-    UnknownSymbol(String),
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GameResult {
+    Lose,
+    Draw,
+    Win,
+}
 
-    #[error("Invalid format string {0}")]
-    InvalidFormat(String),
+const RESULTS: [GameResult; 3] = [GameResult::Lose, GameResult::Draw, GameResult::Win];
+
+/// How the second column of the strategy guide is read.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Strategy {
+    /// The shape we play.
+    Shape,
+    /// The result we are told to get, we play whatever shape gets it.
+    Outcome,
 }
 
-#[derive(Copy, Clone, Eq, PartialEq)]
-enum Shape {
-    Rock,
-    Paper,
-    Scissors,
+pub struct ShapeRule {
+    pub name: String,
+    pub score: u32,
+    /// Symbol in the opponent's column.
+    pub opponent: char,
+    /// Symbol in our column when it is read as `Strategy::Shape`.
+    pub player: char,
 }
 
-impl Shape {
-    fn get_winning_symbol(&self) -> Shape {
-        match self {
-            Shape::Rock => Shape::Paper,
-            Shape::Paper => Shape::Scissors,
-            Shape::Scissors => Shape::Rock,
+impl ShapeRule {
+    pub fn new(name: &str, score: u32, opponent: char, player: char) -> Self {
+        ShapeRule {
+            name: name.to_string(),
+            score,
+            opponent,
+            player,
         }
     }
+}
+
+/// Everything that makes a variant of the game: the shapes, who beats whom and how rounds
+/// are scored.
+pub struct Rules {
+    shapes: Vec<ShapeRule>,
+    /// `beats[a][b]` when shape `a` beats shape `b`.
+    beats: Vec<Vec<bool>>,
+    /// Symbols for losing, drawing and winning when our column is read as `Strategy::Outcome`.
+    outcome_symbols: [char; 3],
+    /// Points for losing, drawing and winning a round.
+    outcome_scores: [u32; 3],
+}
 
-    fn get_losing_symbol(&self) -> Shape {
-        match self {
-            Shape::Rock => Shape::Scissors,
-            Shape::Paper => Shape::Rock,
-            Shape::Scissors => Shape::Paper,
+fn check_distinct<I>(symbols: I, column: &str) -> AocResult<()>
+where
+    I: IntoIterator<Item = char>,
+{
+    let mut seen = vec![];
+    for symbol in symbols {
+        if seen.contains(&symbol) {
+            return Err(AocError::InvalidRules(format!(
+                "{:?} is used twice in the {} column",
+                symbol, column
+            )));
         }
+        seen.push(symbol);
     }
+    Ok(())
+}
 
-    fn get_score(&self) -> u32 {
-        match self {
-            Shape::Rock => 1,
-            Shape::Paper => 2,
-            Shape::Scissors => 3,
-        }
+fn symbol_index<I>(symbols: I, s: &str) -> AocResult<usize>
+where
+    I: IntoIterator<Item = char>,
+{
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(symbol), None) => symbols.into_iter().position(|c| c == symbol),
+        _ => None,
     }
+    .ok_or_else(|| AocError::UnknownSymbol(s.to_string()))
 }
 
-impl FromStr for Shape {
-    type Err = ParsingError;
+impl Rules {
+    /// `beats` lists `(winner, loser)` pairs of shape positions. Every two shapes need exactly
+    /// one winner, and every shape has to beat and lose to something so that any outcome can
+    /// be played.
+    pub fn new(shapes: Vec<ShapeRule>, beats: &[(usize, usize)]) -> AocResult<Self> {
+        let count = shapes.len();
+        if count < 3 {
+            return Err(AocError::InvalidRules(format!(
+                "{} shapes are not enough for a game",
+                count
+            )));
+        }
+        check_distinct(shapes.iter().map(|shape| shape.opponent), "opponent")?;
+        check_distinct(shapes.iter().map(|shape| shape.player), "player")?;
+        let mut table = vec![vec![false; count]; count];
+        for &(winner, loser) in beats {
+            if winner >= count || loser >= count || winner == loser {
+                return Err(AocError::InvalidRules(format!(
+                    "shape {} can not beat shape {}",
+                    winner, loser
+                )));
+            }
+            table[winner][loser] = true;
+        }
+        for a in 0..count {
+            for b in a + 1..count {
+                if table[a][b] == table[b][a] {
+                    return Err(AocError::InvalidRules(format!(
+                        "{} and {} need exactly one winner",
+                        shapes[a].name, shapes[b].name
+                    )));
+                }
+            }
+            if !table[a].contains(&true) || !table.iter().any(|row| row[a]) {
+                return Err(AocError::InvalidRules(format!(
+                    "{} has to both win and lose against some shape",
+                    shapes[a].name
+                )));
+            }
+        }
+        Ok(Rules {
+            shapes,
+            beats: table,
+            outcome_symbols: ['X', 'Y', 'Z'],
+            outcome_scores: [0, 3, 6],
+        })
+    }
+
+    /// Symbols and points for losing, drawing and winning, in that order.
+    pub fn with_outcomes(mut self, symbols: [char; 3], scores: [u32; 3]) -> AocResult<Self> {
+        check_distinct(symbols, "outcome")?;
+        self.outcome_symbols = symbols;
+        self.outcome_scores = scores;
+        Ok(self)
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "X" | "A" => Ok(Shape::Rock),
-            "Y" | "B" => Ok(Shape::Paper),
-            "Z" | "C" => Ok(Shape::Scissors),
-            _ => Err(ParsingError::UnknownSymbol(s.to_string())),
+    /// An odd number of shapes in a circle, each beating the half of the circle just before it.
+    /// Shapes are given as name, opponent symbol and player symbol, and shape `i` scores `i + 1`.
+    pub fn cycle(shapes: &[(&str, char, char)]) -> AocResult<Self> {
+        let count = shapes.len();
+        if count.is_multiple_of(2) {
+            return Err(AocError::InvalidRules(format!(
+                "a cycle of {} shapes has ties",
+                count
+            )));
         }
+        let beats = (0..count)
+            .flat_map(|winner| (1..=count / 2).map(move |k| (winner, (winner + count - k) % count)))
+            .collect::<Vec<_>>();
+        let shapes = shapes
+            .iter()
+            .enumerate()
+            .map(|(i, &(name, opponent, player))| {
+                ShapeRule::new(name, i as u32 + 1, opponent, player)
+            })
+            .collect();
+        Self::new(shapes, &beats)
     }
-}
 
-enum GameResult {
-    Win,
-    Draw,
-    Lose,
-}
+    /// The puzzle's rock, paper and scissors.
+    pub fn classic() -> Self {
+        Self::cycle(&[
+            ("Rock", 'A', 'X'),
+            ("Paper", 'B', 'Y'),
+            ("Scissors", 'C', 'Z'),
+        ])
+        .unwrap()
+    }
 
-impl FromStr for GameResult {
-    type Err = ParsingError;
+    /// Rock, paper, scissors, lizard, Spock. The first three keep their classic symbols and
+    /// scores, lizard is `D`/`V` for 4 and Spock is `E`/`W` for 5.
+    pub fn rpsls() -> Self {
+        let shapes = vec![
+            ShapeRule::new("Rock", 1, 'A', 'X'),
+            ShapeRule::new("Paper", 2, 'B', 'Y'),
+            ShapeRule::new("Scissors", 3, 'C', 'Z'),
+            ShapeRule::new("Lizard", 4, 'D', 'V'),
+            ShapeRule::new("Spock", 5, 'E', 'W'),
+        ];
+        let beats = [
+            (0, 2),
+            (0, 3),
+            (1, 0),
+            (1, 4),
+            (2, 1),
+            (2, 3),
+            (3, 1),
+            (3, 4),
+            (4, 0),
+            (4, 2),
+        ];
+        Self::new(shapes, &beats).unwrap()
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "X" => Ok(GameResult::Lose),
-            "Y" => Ok(GameResult::Draw),
-            "Z" => Ok(GameResult::Win),
-            _ => Err(ParsingError::UnknownSymbol(s.to_string())),
-        }
+    pub fn shapes(&self) -> impl Iterator<Item = Shape> {
+        (0..self.shapes.len()).map(Shape)
     }
-}
 
-struct Game {
-    player: Shape,
-    enemy: Shape,
-}
+    pub fn name(&self, shape: Shape) -> &str {
+        &self.shapes[shape.0].name
+    }
 
-impl Game {
-    fn get_player_result(&self) -> GameResult {
-        if self.player == self.enemy.get_winning_symbol() {
+    pub fn result(&self, player: Shape, enemy: Shape) -> GameResult {
+        if self.beats[player.0][enemy.0] {
             GameResult::Win
-        } else if self.player == self.enemy.get_losing_symbol() {
+        } else if self.beats[enemy.0][player.0] {
             GameResult::Lose
         } else {
             GameResult::Draw
         }
     }
 
-    fn get_player_score(&self) -> u32 {
-        let outcome_score = match self.get_player_result() {
-            GameResult::Win => 6,
-            GameResult::Draw => 3,
-            GameResult::Lose => 0,
+    pub fn score(&self, game: &Game) -> u32 {
+        self.shapes[game.player.0].score
+            + self.outcome_scores[self.result(game.player, game.enemy) as usize]
+    }
+
+    /// What every one of our shapes scores against `enemy`, in shape order.
+    pub fn round_scores(&self, enemy: Shape) -> Vec<u32> {
+        self.shapes()
+            .map(|player| self.score(&Game { player, enemy }))
+            .collect()
+    }
+
+    /// The first shape that gets `result` against `enemy`, the rules guarantee there is one.
+    pub fn shape_for(&self, enemy: Shape, result: GameResult) -> Shape {
+        self.shapes()
+            .find(|&shape| self.result(shape, enemy) == result)
+            .unwrap()
+    }
+
+    /// Reads one line of the strategy guide, with our column read according to `strategy`.
+    pub fn parse_game(&self, line: &str, strategy: Strategy) -> AocResult<Game> {
+        let mut columns = line.split(' ');
+        let mut next_column = || {
+            columns
+                .next()
+                .ok_or(AocError::InvalidFormat(line.to_string()))
+        };
+        let enemy = Shape(symbol_index(
+            self.shapes.iter().map(|shape| shape.opponent),
+            next_column()?,
+        )?);
+        let column = next_column()?;
+        let player = match strategy {
+            Strategy::Shape => Shape(symbol_index(
+                self.shapes.iter().map(|shape| shape.player),
+                column,
+            )?),
+            Strategy::Outcome => {
+                let result = RESULTS[symbol_index(self.outcome_symbols, column)?];
+                self.shape_for(enemy, result)
+            }
         };
-        self.player.get_score() + outcome_score
-    }
-}
-
-impl FromStr for Game {
-    type Err = ParsingError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut splitted_string = s.split(" ");
-        let opponent_shape = splitted_string
-            .next()
-            .ok_or(ParsingError::InvalidFormat(s.to_string()))
-            .and_then(|s| Shape::from_str(s))?;
-        let player_shape = splitted_string
-            .next()
-            .ok_or(ParsingError::InvalidFormat(s.to_string()))
-            .and_then(|s| Shape::from_str(s))?;
-        Ok(Game {
-            player: player_shape,
-            enemy: opponent_shape,
+        Ok(Game { player, enemy })
+    }
+}
+
+pub struct Game {
+    pub player: Shape,
+    pub enemy: Shape,
+}
+
+/// What the guide could have scored given only the opponent's column.
+pub struct Analysis {
+    pub max: u32,
+    pub min: u32,
+    /// `distribution[total]` is the chance of scoring `total` when we pick every shape uniformly
+    /// at random.
+    pub distribution: Vec<f64>,
+    pub shape_total: u32,
+    pub outcome_total: u32,
+}
+
+impl Analysis {
+    pub fn expected(&self) -> f64 {
+        self.distribution
+            .iter()
+            .enumerate()
+            .map(|(total, chance)| total as f64 * chance)
+            .sum()
+    }
+
+    /// The reading of our column that scores more, `None` when both score the same.
+    pub fn better_strategy(&self) -> Option<Strategy> {
+        match self.shape_total.cmp(&self.outcome_total) {
+            Ordering::Greater => Some(Strategy::Shape),
+            Ordering::Less => Some(Strategy::Outcome),
+            Ordering::Equal => None,
+        }
+    }
+}
+
+/// The strategy guide is kept as raw lines since each part reads the second column differently.
+pub struct Day2 {
+    lines: Vec<String>,
+    rules: Rules,
+}
+
+impl Day2 {
+    /// Plays the same guide by different rules.
+    pub fn with_rules(self, rules: Rules) -> Self {
+        Day2 { rules, ..self }
+    }
+
+    /// Blank lines are skipped, they are not rounds.
+    fn games(&self, mode: Mode, strategy: Strategy) -> AocResult<Diagnosed<Vec<Game>>> {
+        let games = check_lines(&self.lines, mode, |line| {
+            if line.trim().is_empty() {
+                Ok(None)
+            } else {
+                self.rules.parse_game(line, strategy).map(Some)
+            }
+        })?;
+        Ok(games.map(|games| games.into_iter().flatten().collect()))
+    }
+
+    pub fn score(&self, mode: Mode, strategy: Strategy) -> AocResult<Diagnosed<u32>> {
+        let games = self.games(mode, strategy)?;
+        Ok(games.map(|games| games.iter().map(|game| self.rules.score(game)).sum()))
+    }
+
+    /// Rejects the guide like part one and two do, then looks at every round on its own.
+    pub fn analyse(&self) -> AocResult<Analysis> {
+        let shape_total = self.shape_score(Mode::Strict)?.value;
+        let outcome_total = self.outcome_score(Mode::Strict)?.value;
+        let enemies = self
+            .games(Mode::Strict, Strategy::Shape)?
+            .value
+            .into_iter()
+            .map(|game| game.enemy);
+        let round_scores = self
+            .rules
+            .shapes()
+            .map(|enemy| self.rules.round_scores(enemy))
+            .collect::<Vec<_>>();
+        let mut max = 0;
+        let mut min = 0;
+        let mut distribution = vec![1.0];
+        for enemy in enemies {
+            let scores = &round_scores[enemy.0];
+            let best = *scores.iter().max().unwrap();
+            max += best;
+            min += *scores.iter().min().unwrap();
+            let chance = 1.0 / scores.len() as f64;
+            let mut next = vec![0.0; distribution.len() + best as usize];
+            for (total, before) in distribution.iter().enumerate() {
+                for &score in scores {
+                    next[total + score as usize] += before * chance;
+                }
+            }
+            distribution = next;
+        }
+        Ok(Analysis {
+            max,
+            min,
+            distribution,
+            shape_total,
+            outcome_total,
         })
     }
+
+    pub fn shape_score(&self, mode: Mode) -> AocResult<Diagnosed<u32>> {
+        self.score(mode, Strategy::Shape)
+    }
+
+    pub fn outcome_score(&self, mode: Mode) -> AocResult<Diagnosed<u32>> {
+        self.score(mode, Strategy::Outcome)
+    }
+}
+
+impl Solution for Day2 {
+    fn parse<R>(input: R) -> AocResult<Self>
+    where
+        R: BufRead,
+    {
+        Ok(Day2 {
+            lines: input.lines().collect::<Result<_, _>>()?,
+            rules: Rules::classic(),
+        })
+    }
+
+    fn part_one(&self) -> AocResult<Answer> {
+        Ok(self.shape_score(Mode::Strict)?.value.into())
+    }
+
+    fn part_two(&self) -> AocResult<Answer> {
+        Ok(self.outcome_score(Mode::Strict)?.value.into())
+    }
 }
 
-fn task_b_from_str(s: &str) -> Result<Game, ParsingError> {
-    let mut splitted_string = s.split(" ");
-    let opponent_shape = splitted_string
-        .next()
-        .ok_or(ParsingError::InvalidFormat(s.to_string()))
-        .and_then(|s| Shape::from_str(s))?;
-    let game_result = splitted_string
-        .next()
-        .ok_or(ParsingError::InvalidFormat(s.to_string()))
-        .and_then(|s| GameResult::from_str(s))?;
-    let player_shape = match game_result {
-        GameResult::Win => opponent_shape.get_winning_symbol(),
-        GameResult::Draw => opponent_shape,
-        GameResult::Lose => opponent_shape.get_losing_symbol(),
-    };
-    Ok(Game {
-        player: player_shape,
-        enemy: opponent_shape,
-    })
-}
-
-pub fn task_a<P>(file: P) -> io::Result<u32>
+impl Streaming for Day2 {
+    fn stream<R>(input: R) -> AocResult<[Answer; 2]>
+    where
+        R: BufRead,
+    {
+        let rules = Rules::classic();
+        let mut shape_score = 0u64;
+        let mut outcome_score = 0u64;
+        for_each_line(input, |line, text| {
+            if text.trim().is_empty() {
+                return Ok(());
+            }
+            let score = |strategy| {
+                rules
+                    .parse_game(text, strategy)
+                    .map(|game| u64::from(rules.score(&game)))
+                    .map_err(|e| AocError::at_line(line, e))
+            };
+            shape_score += score(Strategy::Shape)?;
+            outcome_score += score(Strategy::Outcome)?;
+            Ok(())
+        })?;
+        Ok([shape_score.into(), outcome_score.into()])
+    }
+}
+
+impl Generate for Day2 {
+    fn generate<R>(rng: &mut R, size: usize) -> String
+    where
+        R: Rng,
+    {
+        (0..size.max(1))
+            .map(|_| {
+                let opponent = ["A", "B", "C"].choose(rng).unwrap();
+                let player = ["X", "Y", "Z"].choose(rng).unwrap();
+                format!("{} {}\n", opponent, player)
+            })
+            .collect()
+    }
+}
+
+pub fn task_a_with_mode<P>(file: P, mode: Mode) -> AocResult<Diagnosed<u32>>
+where
+    P: AsRef<Path>,
+{
+    Day2::parse_file(file)?.shape_score(mode)
+}
+
+pub fn task_b_with_mode<P>(file: P, mode: Mode) -> AocResult<Diagnosed<u32>>
 where
     P: AsRef<Path>,
 {
-    Ok(utils::get_input_file(file)?
-        .lines()
-        .par_bridge()
-        .filter_map(|line| line.ok())
-        .filter_map(|line| Game::from_str(&line).ok())
-        .map(|game| game.get_player_score())
-        .sum())
+    Day2::parse_file(file)?.outcome_score(mode)
 }
 
-pub fn task_b<P>(file: P) -> io::Result<u32>
+pub fn task_a<P>(file: P) -> AocResult<u32>
 where
     P: AsRef<Path>,
 {
-    Ok(utils::get_input_file(file)?
-        .lines()
-        .par_bridge()
-        .filter_map(|line| line.ok())
-        .filter_map(|line| task_b_from_str(&line).ok())
-        .map(|game| game.get_player_score())
-        .sum())
+    Ok(task_a_with_mode(file, Mode::Strict)?.value)
+}
+
+pub fn task_b<P>(file: P) -> AocResult<u32>
+where
+    P: AsRef<Path>,
+{
+    Ok(task_b_with_mode(file, Mode::Strict)?.value)
 }
 
 #[cfg(test)]
 mod tests {
+    use super::Strategy;
     use super::*;
+    use itertools::Itertools;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
 
     #[test]
     fn test_a_works() {
@@ -190,4 +492,171 @@ mod tests {
         let result = task_b("data/day2t.txt").unwrap();
         assert_eq!(result, 12)
     }
+
+    #[test]
+    fn parse_str_works() {
+        let result = Day2::parse_str("A Y\nB X\nC Z").unwrap();
+        assert_eq!(result.part_one().unwrap(), Answer::Number(15));
+        assert_eq!(result.part_two().unwrap(), Answer::Number(12));
+    }
+
+    #[test]
+    fn unknown_symbol_is_reported() {
+        let result = Day2::parse_str("A Y\nQ X\nC Z").unwrap();
+        assert_eq!(
+            result.part_one().unwrap_err().to_string(),
+            "Line 2: Unknown symbol Q"
+        );
+        let lenient = result.outcome_score(Mode::Lenient).unwrap();
+        assert_eq!(lenient.value, 11);
+        assert_eq!(lenient.diagnostics.len(), 1);
+        assert_eq!(lenient.diagnostics[0].line, 2);
+    }
+
+    #[test]
+    fn blank_lines_are_skipped() {
+        let input = "A Y\n\nB X\nC Z\n\n";
+        let day = Day2::parse_str(input).unwrap();
+        assert_eq!(day.part_one().unwrap(), Answer::Number(15));
+        assert_eq!(day.part_two().unwrap(), Answer::Number(12));
+        assert_eq!(
+            Day2::stream(input.as_bytes()).unwrap(),
+            [15u64.into(), 12u64.into()]
+        );
+        let error = Day2::parse_str("A Y\n\nQ X").unwrap().part_one();
+        assert_eq!(error.unwrap_err().to_string(), "Line 3: Unknown symbol Q");
+    }
+
+    #[test]
+    fn variants_are_scored() {
+        let day = Day2::parse_str("A Z\nE X\nD Y")
+            .unwrap()
+            .with_rules(Rules::rpsls());
+        assert_eq!(day.shape_score(Mode::Strict).unwrap().value, 3 + 1 + 2);
+        assert_eq!(day.outcome_score(Mode::Strict).unwrap().value, 8 + 1 + 7);
+        let error = day
+            .with_rules(Rules::classic())
+            .shape_score(Mode::Strict)
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Line 2: Unknown symbol E");
+
+        let shapes = [
+            ("One", 'A', 'V'),
+            ("Two", 'B', 'W'),
+            ("Three", 'C', 'X'),
+            ("Four", 'D', 'Y'),
+            ("Five", 'E', 'Z'),
+        ];
+        let rules = Rules::cycle(&shapes).unwrap();
+        let game = rules.parse_game("E V", Strategy::Shape).unwrap();
+        assert_eq!(rules.result(game.player, game.enemy), GameResult::Win);
+        let game = rules.parse_game("A Y", Strategy::Shape).unwrap();
+        assert_eq!(rules.result(game.player, game.enemy), GameResult::Lose);
+    }
+
+    #[test]
+    fn guide_is_analysed() {
+        let analysis = Day2::parse_file("data/day2t.txt")
+            .unwrap()
+            .analyse()
+            .unwrap();
+        assert_eq!((analysis.max, analysis.min), (8 + 9 + 7, 3 + 1 + 2));
+        assert_eq!(analysis.better_strategy(), Some(Strategy::Shape));
+        assert!((analysis.expected() - 15.0).abs() < 1e-9);
+
+        let rules = Rules::classic();
+        let rounds = [Shape(0), Shape(1), Shape(2)].map(|enemy| rules.round_scores(enemy));
+        let mut counts = vec![0; analysis.distribution.len()];
+        for picks in (0..3).map(|_| 0..3).multi_cartesian_product() {
+            let total = (0..3).map(|round| rounds[round][picks[round]]).sum::<u32>();
+            counts[total as usize] += 1;
+        }
+        for (chance, count) in analysis.distribution.iter().zip(counts) {
+            assert!((chance - count as f64 / 27.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn invalid_rules_are_refused() {
+        let shapes = [
+            ("Rock", 'A', 'X'),
+            ("Paper", 'B', 'Y'),
+            ("Scissors", 'C', 'Z'),
+            ("Well", 'D', 'W'),
+        ];
+        assert!(matches!(
+            Rules::cycle(&shapes),
+            Err(AocError::InvalidRules(_))
+        ));
+        let shapes = || {
+            vec![
+                ShapeRule::new("Rock", 1, 'A', 'X'),
+                ShapeRule::new("Paper", 2, 'B', 'Y'),
+                ShapeRule::new("Scissors", 3, 'C', 'X'),
+            ]
+        };
+        assert_eq!(
+            Rules::new(shapes(), &[(1, 0), (2, 1), (0, 2)])
+                .err()
+                .unwrap()
+                .to_string(),
+            "Invalid rules: 'X' is used twice in the player column"
+        );
+        let mut fixed = shapes();
+        fixed[2].player = 'Z';
+        assert_eq!(
+            Rules::new(fixed, &[(1, 0), (2, 1)])
+                .err()
+                .unwrap()
+                .to_string(),
+            "Invalid rules: Rock and Scissors need exactly one winner"
+        );
+    }
+
+    /// Rock, paper and scissors as 0, 1 and 2, paired with the shape each one beats.
+    const BEATS: [(usize, usize); 3] = [(0, 2), (1, 0), (2, 1)];
+
+    fn outcome(player: usize, opponent: usize) -> u64 {
+        if player == opponent {
+            3
+        } else if BEATS.contains(&(player, opponent)) {
+            6
+        } else {
+            0
+        }
+    }
+
+    /// Tries every shape to find the one giving the wanted outcome.
+    fn reference(rounds: &[(usize, usize)]) -> (u64, u64) {
+        let shapes = rounds
+            .iter()
+            .map(|&(opponent, player)| player as u64 + 1 + outcome(player, opponent))
+            .sum();
+        let outcomes = rounds
+            .iter()
+            .map(|&(opponent, column)| {
+                let wanted = column as u64 * 3;
+                let player = (0..3)
+                    .find(|&player| outcome(player, opponent) == wanted)
+                    .unwrap();
+                player as u64 + 1 + wanted
+            })
+            .sum();
+        (shapes, outcomes)
+    }
+
+    proptest! {
+        #[test]
+        fn matches_reference(rounds in vec((0..3usize, 0..3usize), 1..50)) {
+            let input = rounds
+                .iter()
+                .map(|&(opponent, player)| format!("{} {}\n", &"ABC"[opponent..=opponent], &"XYZ"[player..=player]))
+                .collect::<String>();
+            let (shapes, outcomes) = reference(&rounds);
+            let day = Day2::parse_str(&input).unwrap();
+            prop_assert_eq!(day.part_one().unwrap(), shapes.into());
+            prop_assert_eq!(day.part_two().unwrap(), outcomes.into());
+            prop_assert_eq!(Day2::stream(input.as_bytes()).unwrap(), [shapes.into(), outcomes.into()]);
+        }
+    }
 }
@@ -1,95 +1,410 @@
-use itertools::Itertools;
 use rayon::prelude::*;
-use std::collections::{BTreeSet, HashSet};
+use std::fmt::{Display, Formatter};
 use std::fs::File;
-use std::io;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
 
-use crate::utils;
-use thiserror::Error;
+use crate::diagnostics::{collect_checked, Diagnosed, Diagnostic, Mode};
+use crate::error::{AocError, AocResult};
+use crate::generate::{letters_from, Generate, LETTERS};
+use crate::solution::{Answer, Part, Solution};
+use crate::streaming::{for_each_line, Streaming};
+use rand::seq::SliceRandom;
+use rand::Rng;
 
-#[derive(Error, Debug)]
-enum AsciiError {
-    #[error("Not ASCII symbol")]
-    NotAsciiSymbol(char),
-
-    #[error("Not a letter")]
-    NotALetter(char),
-}
-
-fn char_to_priority(c: char) -> Result<u8, AsciiError> {
+fn char_to_priority(c: char) -> AocResult<u8> {
     if !c.is_ascii() {
-        return Err(AsciiError::NotAsciiSymbol(c));
+        return Err(AocError::NotAsciiSymbol(c));
     }
     if !c.is_alphabetic() {
-        return Err(AsciiError::NotALetter(c));
+        return Err(AocError::NotALetter(c));
     }
     let ascii_code = c as u8;
-    if ascii_code >= 65 && ascii_code <= 90 {
+    if (65..=90).contains(&ascii_code) {
         return Ok(ascii_code - 38);
-    } else if ascii_code >= 97 && ascii_code <= 122 {
+    } else if (97..=122).contains(&ascii_code) {
         return Ok(ascii_code - 96);
     }
     Ok(0)
 }
 
-fn get_rucksack_priority(rucksack: &str) -> Option<u64> {
-    let string_len = rucksack.len();
-    let chars_slice = rucksack.chars().collect::<Vec<char>>();
-    let (first, second) = chars_slice.split_at(string_len / 2);
-    let first = BTreeSet::from_iter(first.to_vec().iter().cloned());
-    let second = BTreeSet::from_iter(second.to_vec().iter().cloned());
-    let intersection = &first & &second;
-    Some(
-        intersection
+/// A set of items as a 52-bit mask where bit `priority - 1` stands for the item with that
+/// priority.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct ItemSet(u64);
+
+impl ItemSet {
+    pub const ALL: ItemSet = ItemSet((1 << 52) - 1);
+
+    pub fn from_items(items: &str) -> AocResult<Self> {
+        items.chars().try_fold(ItemSet::default(), ItemSet::with)
+    }
+
+    fn with(self, item: char) -> AocResult<Self> {
+        Ok(ItemSet(self.0 | 1 << (char_to_priority(item)? - 1)))
+    }
+
+    pub fn intersection(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 & other.0)
+    }
+
+    pub fn union(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 | other.0)
+    }
+
+    pub fn difference(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 & !other.0)
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Priorities of the items, lowest first.
+    pub fn priorities(self) -> impl Iterator<Item = u8> {
+        let mut bits = self.0;
+        std::iter::from_fn(move || {
+            let priority = bits.trailing_zeros() as u8 + 1;
+            bits &= bits.checked_sub(1)?;
+            Some(priority)
+        })
+    }
+
+    pub fn priority_sum(self) -> u64 {
+        self.priorities().map(u64::from).sum()
+    }
+}
+
+impl Display for ItemSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.priorities()
+            .try_for_each(|priority| write!(f, "{}", LETTERS[priority as usize - 1] as char))
+    }
+}
+
+fn rucksack_common(rucksack: &str, compartments: usize) -> AocResult<ItemSet> {
+    if let Some(item) = rucksack.chars().find(|item| !item.is_ascii()) {
+        return Err(AocError::NotAsciiSymbol(item));
+    }
+    let length = rucksack.len();
+    if !length.is_multiple_of(compartments) {
+        return Err(AocError::UnevenCompartments {
+            length,
+            compartments,
+        });
+    }
+    if length == 0 {
+        return Ok(ItemSet::default());
+    }
+    let size = length / compartments;
+    let mut common = ItemSet::ALL;
+    for start in (0..length).step_by(size) {
+        common = common.intersection(ItemSet::from_items(&rucksack[start..start + size])?);
+    }
+    Ok(common)
+}
+
+/// Works for groups of any size, an empty group has no badge.
+fn group_badge<S>(group: &[S]) -> AocResult<ItemSet>
+where
+    S: AsRef<str>,
+{
+    Ok(group
+        .iter()
+        .map(|rucksack| ItemSet::from_items(rucksack.as_ref()))
+        .collect::<AocResult<Vec<_>>>()?
+        .into_iter()
+        .reduce(ItemSet::intersection)
+        .unwrap_or_default())
+}
+
+/// The items one rucksack or one group has in common, numbered by its first line.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Shared {
+    pub line: usize,
+    pub items: ItemSet,
+}
+
+impl Shared {
+    pub fn priority(&self) -> u64 {
+        self.items.priority_sum()
+    }
+}
+
+fn total_priority(shared: Diagnosed<Vec<Shared>>) -> Diagnosed<u64> {
+    shared.map(|shared| shared.iter().map(Shared::priority).sum())
+}
+
+/// How many elves carry the same badge and how many equal compartments every rucksack has.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Packing {
+    group_size: usize,
+    compartments: usize,
+}
+
+impl Default for Packing {
+    fn default() -> Self {
+        Packing {
+            group_size: 3,
+            compartments: 2,
+        }
+    }
+}
+
+impl Packing {
+    pub fn new(group_size: usize, compartments: usize) -> AocResult<Self> {
+        if group_size == 0 || compartments == 0 {
+            return Err(AocError::InvalidPacking {
+                group_size,
+                compartments,
+            });
+        }
+        Ok(Packing {
+            group_size,
+            compartments,
+        })
+    }
+
+    pub fn group_size(&self) -> usize {
+        self.group_size
+    }
+
+    pub fn compartments(&self) -> usize {
+        self.compartments
+    }
+}
+
+/// Blank lines are left out, `lines` keeps the input line of every rucksack for reporting.
+pub struct Day3 {
+    rucksacks: Vec<String>,
+    lines: Vec<usize>,
+    packing: Packing,
+}
+
+impl Day3 {
+    pub fn with_packing(self, packing: Packing) -> Self {
+        Day3 { packing, ..self }
+    }
+
+    fn text(&self, line: usize) -> String {
+        match self.lines.binary_search(&line) {
+            Ok(i) => self.rucksacks[i].clone(),
+            Err(_) => String::new(),
+        }
+    }
+
+    fn compartment_items(&self, mode: Mode) -> AocResult<Diagnosed<Vec<Shared>>> {
+        let shared = self
+            .rucksacks
+            .par_iter()
+            .zip(&self.lines)
+            .map(|(rucksack, &line)| {
+                let items = rucksack_common(rucksack, self.packing.compartments);
+                (line, items.map(|items| Shared { line, items }))
+            })
+            .collect::<Vec<_>>();
+        collect_checked(shared, mode, |line| self.text(line))
+    }
+
+    fn badge_items(&self, mode: Mode) -> AocResult<Diagnosed<Vec<Shared>>> {
+        let size = self.packing.group_size;
+        let shared = self
+            .rucksacks
+            .par_chunks(size)
+            .enumerate()
+            .map(|(i, group)| {
+                let line = self.lines[i * size];
+                let items = match group.len() {
+                    found if found < size => Err(AocError::IncompleteGroup {
+                        found,
+                        expected: size,
+                    }),
+                    _ => group_badge(group),
+                };
+                (line, items.map(|items| Shared { line, items }))
+            })
+            .collect::<Vec<_>>();
+        collect_checked(shared, mode, |line| self.text(line))
+    }
+
+    pub fn compartments_priority(&self, mode: Mode) -> AocResult<Diagnosed<u64>> {
+        Ok(total_priority(self.compartment_items(mode)?))
+    }
+
+    /// Groups are reported by the line number of their first rucksack.
+    pub fn badges_priority(&self, mode: Mode) -> AocResult<Diagnosed<u64>> {
+        Ok(total_priority(self.badge_items(mode)?))
+    }
+
+    /// What every rucksack shares between its compartments for part a, or every group for
+    /// part b.
+    pub fn shared_items(&self, part: Part) -> AocResult<Vec<Shared>> {
+        let shared = match part {
+            Part::A => self.compartment_items(Mode::Strict)?,
+            Part::B => self.badge_items(Mode::Strict)?,
+        };
+        Ok(shared.value)
+    }
+
+    /// The puzzle promises exactly one shared item everywhere, this reports where it does not
+    /// hold.
+    pub fn validate(&self, part: Part) -> AocResult<Vec<Diagnostic>> {
+        Ok(self
+            .shared_items(part)?
             .into_iter()
-            .map(|c| char_to_priority(c).unwrap() as u64)
-            .sum(),
-    )
+            .filter(|shared| shared.items.len() != 1)
+            .map(|shared| Diagnostic {
+                line: shared.line,
+                text: self.text(shared.line),
+                error: AocError::SharedItems(shared.items.to_string()),
+            })
+            .collect())
+    }
 }
 
-pub fn task_a<P>(file: P) -> io::Result<u64>
+impl Solution for Day3 {
+    fn parse<R>(input: R) -> AocResult<Self>
+    where
+        R: BufRead,
+    {
+        let mut rucksacks = vec![];
+        let mut lines = vec![];
+        for_each_line(input, |line, text| {
+            if !text.trim().is_empty() {
+                rucksacks.push(text.to_string());
+                lines.push(line);
+            }
+            Ok(())
+        })?;
+        Ok(Day3 {
+            rucksacks,
+            lines,
+            packing: Packing::default(),
+        })
+    }
+
+    fn part_one(&self) -> AocResult<Answer> {
+        Ok(self.compartments_priority(Mode::Strict)?.value.into())
+    }
+
+    fn part_two(&self) -> AocResult<Answer> {
+        Ok(self.badges_priority(Mode::Strict)?.value.into())
+    }
+}
+
+impl Streaming for Day3 {
+    fn stream<R>(input: R) -> AocResult<[Answer; 2]>
+    where
+        R: BufRead,
+    {
+        let packing = Packing::default();
+        let mut compartments = 0;
+        let mut badges = 0;
+        let mut group_line = 0;
+        let mut group = Vec::with_capacity(packing.group_size);
+        for_each_line(input, |line, text| {
+            if text.trim().is_empty() {
+                return Ok(());
+            }
+            if group.is_empty() {
+                group_line = line;
+            }
+            compartments += rucksack_common(text, packing.compartments)
+                .map_err(|e| AocError::at_line(line, e))?
+                .priority_sum();
+            group.push(ItemSet::from_items(text).map_err(|e| AocError::at_line(line, e))?);
+            if group.len() == packing.group_size {
+                let badge = group.drain(..).reduce(ItemSet::intersection).unwrap();
+                badges += badge.priority_sum();
+            }
+            Ok(())
+        })?;
+        if !group.is_empty() {
+            let error = AocError::IncompleteGroup {
+                found: group.len(),
+                expected: packing.group_size,
+            };
+            return Err(AocError::at_line(group_line, error));
+        }
+        Ok([compartments.into(), badges.into()])
+    }
+}
+
+impl Generate for Day3 {
+    /// Every group shares exactly one badge and every rucksack exactly one item between its
+    /// compartments. `size` is rounded up to whole groups.
+    fn generate<R>(rng: &mut R, size: usize) -> String
+    where
+        R: Rng,
+    {
+        let mut input = String::new();
+        for _ in 0..size.div_ceil(3).max(1) {
+            let mut letters = LETTERS.iter().map(|&l| l as char).collect::<Vec<_>>();
+            letters.shuffle(rng);
+            let badge = letters[0];
+            // Disjoint pools per elf, so the badge is the only item all three carry.
+            for pool in letters[1..].chunks(17) {
+                let mut pool = pool.iter().copied().chain([badge]).collect::<Vec<_>>();
+                pool.shuffle(rng);
+                let shared = pool[0];
+                let (first, second) = pool[1..].split_at(pool.len() / 2);
+                let half = rng.gen_range(2..=12);
+                let mut left = vec![shared];
+                left.extend(letters_from(rng, first, half - 1).chars());
+                let mut right = vec![shared];
+                right.extend(letters_from(rng, second, half - 1).chars());
+                if first.contains(&badge) {
+                    left[1] = badge;
+                } else if second.contains(&badge) {
+                    right[1] = badge;
+                }
+                left.shuffle(rng);
+                right.shuffle(rng);
+                input.extend(left.into_iter().chain(right));
+                input.push('\n');
+            }
+        }
+        input
+    }
+}
+
+pub fn task_a_with_mode<P>(file: P, mode: Mode) -> AocResult<Diagnosed<u64>>
 where
     P: AsRef<Path>,
 {
-    Ok(utils::get_input_file(file)?
-        .lines()
-        .par_bridge()
-        .filter_map(|line| line.ok())
-        .filter_map(|line| get_rucksack_priority(&line))
-        .sum())
+    Day3::parse_file(file)?.compartments_priority(mode)
 }
 
-pub fn task_b<P>(file: P) -> io::Result<u64>
+pub fn task_b_with_mode<P>(file: P, mode: Mode) -> AocResult<Diagnosed<u64>>
 where
     P: AsRef<Path>,
 {
-    Ok(utils::get_input_file(file)?
-        .lines()
-        // .par_bridge()
-        .filter_map(|line| line.ok())
-        .tuples::<(_, _, _)>()
-        .map(|tuple| {
-            (
-                BTreeSet::from_iter(tuple.0.chars()),
-                BTreeSet::from_iter(tuple.1.chars()),
-                BTreeSet::from_iter(tuple.2.chars()),
-            )
-        })
-        .map(|group| &(&group.0 & &group.1) & &group.2)
-        .map(|badges| {
-            badges
-                .into_iter()
-                .map(|c| char_to_priority(c).unwrap() as u64)
-                .sum::<u64>()
-        })
-        .sum())
+    Day3::parse_file(file)?.badges_priority(mode)
+}
+
+pub fn task_a<P>(file: P) -> AocResult<u64>
+where
+    P: AsRef<Path>,
+{
+    Ok(task_a_with_mode(file, Mode::Strict)?.value)
+}
+
+pub fn task_b<P>(file: P) -> AocResult<u64>
+where
+    P: AsRef<Path>,
+{
+    Ok(task_b_with_mode(file, Mode::Strict)?.value)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
 
     #[test]
     fn test_a_works() {
@@ -102,4 +417,162 @@ mod tests {
         let result = task_b("data/day3t.txt").unwrap();
         assert_eq!(result, 70)
     }
+
+    #[test]
+    fn non_letters_are_reported() {
+        let result = Day3::parse_str("vJrwpWtwJgWrhcsFMMfFFhFp\na11b").unwrap();
+        assert_eq!(
+            result.part_one().unwrap_err().to_string(),
+            "Line 2: Not a letter '1'"
+        );
+        let lenient = result.compartments_priority(Mode::Lenient).unwrap();
+        assert_eq!(lenient.value, 16);
+        assert_eq!(lenient.diagnostics[0].line, 2);
+    }
+
+    #[test]
+    fn item_sets_combine() {
+        let first = ItemSet::from_items("abcZ").unwrap();
+        let second = ItemSet::from_items("bZZq").unwrap();
+        assert_eq!(
+            first.intersection(second).priorities().collect::<Vec<_>>(),
+            [2, 52]
+        );
+        assert_eq!(first.union(second).len(), 5);
+        assert_eq!(first.difference(second).priority_sum(), 1 + 3);
+        assert!(ItemSet::default().is_empty());
+        assert!(matches!(
+            ItemSet::from_items("a-"),
+            Err(AocError::NotALetter('-'))
+        ));
+    }
+
+    #[test]
+    fn packing_is_configurable() {
+        let day = Day3::parse_str("abab\nbaba").unwrap();
+        assert_eq!(day.part_one().unwrap(), Answer::Number(6));
+        let day = day.with_packing(Packing::new(2, 4).unwrap());
+        assert_eq!(day.part_one().unwrap(), Answer::Number(0));
+        assert_eq!(day.part_two().unwrap(), Answer::Number(3));
+        assert!(matches!(
+            Packing::new(0, 2),
+            Err(AocError::InvalidPacking { .. })
+        ));
+
+        let day = Day3::parse_str("abab\nbaba\nabc").unwrap();
+        assert_eq!(
+            day.part_one().unwrap_err().to_string(),
+            "Line 3: Rucksack of 3 items can not be split into 2 compartments"
+        );
+        let day = day.with_packing(Packing::new(2, 1).unwrap());
+        assert_eq!(
+            day.part_two().unwrap_err().to_string(),
+            "Line 3: Last group has only 1 of 2 rucksacks"
+        );
+        let error = Day3::stream("vJrwpWtwJgWrhcsFMMfFFhFp\n".as_bytes()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Line 1: Last group has only 1 of 3 rucksacks"
+        );
+    }
+
+    #[test]
+    fn shared_items_are_validated() {
+        let day = Day3::parse_str("vJrwpWtwJgWrhcsFMMfFFhFp\nabab\nabcd").unwrap();
+        let breakdown = day
+            .shared_items(Part::A)
+            .unwrap()
+            .iter()
+            .map(|shared| format!("{}, {}, {}", shared.line, shared.items, shared.priority()))
+            .collect::<Vec<_>>();
+        assert_eq!(breakdown, ["1, p, 16", "2, ab, 3", "3, , 0"]);
+        let invalid = day
+            .validate(Part::A)
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            invalid,
+            [
+                "line 2: Expected one shared item, found ab (\"abab\")",
+                "line 3: Expected one shared item, found none (\"abcd\")"
+            ]
+        );
+        let groups = day.validate(Part::B).unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].line, 1);
+        let day = Day3::parse_file("data/day3t.txt").unwrap();
+        assert!(day.validate(Part::A).unwrap().is_empty());
+        assert!(day.validate(Part::B).unwrap().is_empty());
+    }
+
+    #[test]
+    fn blank_lines_are_skipped() {
+        let input = std::fs::read_to_string("data/day3t.txt").unwrap() + "\n";
+        let input = input.replacen('\n', "\n\n", 1);
+        let day = Day3::parse_str(&input).unwrap();
+        assert_eq!(day.part_one().unwrap(), Answer::Number(157));
+        assert_eq!(day.part_two().unwrap(), Answer::Number(70));
+        assert_eq!(
+            Day3::stream(input.as_bytes()).unwrap(),
+            [157u64.into(), 70u64.into()]
+        );
+        let input = "abab\n\nbaba\n\nabab\nbaba\n";
+        let short = "Line 6: Last group has only 1 of 3 rucksacks";
+        let day = Day3::parse_str(input).unwrap();
+        assert_eq!(day.part_two().unwrap_err().to_string(), short);
+        assert_eq!(
+            Day3::stream(input.as_bytes()).unwrap_err().to_string(),
+            short
+        );
+        let day = Day3::parse_str("abab\n\nabc\n").unwrap();
+        assert_eq!(
+            day.part_one().unwrap_err().to_string(),
+            "Line 3: Rucksack of 3 items can not be split into 2 compartments"
+        );
+    }
+
+    fn priority(c: char) -> u64 {
+        LETTERS.iter().position(|&l| l as char == c).unwrap() as u64 + 1
+    }
+
+    /// Sums the distinct items of `first` that every other rucksack also holds.
+    fn reference_common(first: &str, others: &[&str]) -> u64 {
+        let mut seen = vec![];
+        for c in first.chars() {
+            if !seen.contains(&c) && others.iter().all(|other| other.contains(c)) {
+                seen.push(c);
+            }
+        }
+        seen.into_iter().map(priority).sum()
+    }
+
+    fn reference(rucksacks: &[String]) -> (u64, u64) {
+        let compartments = rucksacks
+            .iter()
+            .map(|rucksack| {
+                let (first, second) = rucksack.split_at(rucksack.len() / 2);
+                reference_common(first, &[second])
+            })
+            .sum();
+        let badges = rucksacks
+            .chunks_exact(3)
+            .map(|group| reference_common(&group[0], &[&group[1], &group[2]]))
+            .sum();
+        (compartments, badges)
+    }
+
+    proptest! {
+        #[test]
+        fn matches_reference(groups in vec(vec("([a-zA-Z]{2}){1,8}", 3), 1..10)) {
+            let rucksacks = groups.concat();
+            let input = rucksacks.iter().map(|rucksack| format!("{}\n", rucksack)).collect::<String>();
+            let (compartments, badges) = reference(&rucksacks);
+            let day = Day3::parse_str(&input).unwrap();
+            prop_assert_eq!(day.part_one().unwrap(), compartments.into());
+            prop_assert_eq!(day.part_two().unwrap(), badges.into());
+            prop_assert_eq!(Day3::stream(input.as_bytes()).unwrap(), [compartments.into(), badges.into()]);
+        }
+    }
 }
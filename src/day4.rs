@@ -1,83 +1,164 @@
 use crate::utils;
-use nom::bytes::complete::tag;
-use nom::character::complete::{digit1, line_ending};
-use nom::combinator::{map_parser, map_res};
-use nom::multi::separated_list1;
-use nom::sequence::tuple;
+use itertools::Itertools;
+use nom::combinator::map_opt;
+use nom::error::context;
+use nom::multi::many1;
+use nom::sequence::{pair, preceded, tuple};
 use rayon::prelude::*;
 use std::convert::identity;
 use std::fs::File;
-use std::io;
 use std::io::{BufRead, BufReader};
-use std::ops::RangeInclusive;
 use std::path::Path;
 use std::str::FromStr;
 
-use crate::parsers::integer;
-use thiserror::Error;
+use crate::error::AocResult;
+use crate::generate::Generate;
+use crate::interval::{self, Interval};
+use crate::parsers::{integer, lines, parse_complete, token, ParseResult};
+use crate::solution::{Answer, Solution};
+use crate::streaming::{for_each_record, Streaming};
+use rand::Rng;
 
-type Assignment = (RangeInclusive<u64>, RangeInclusive<u64>);
+const EXPECTED: &str = "expected assignment pair";
 
-#[derive(Error, Debug)]
-enum ParsingError {
-    #[error("Something is wrong")]
-    Err,
-}
+/// The sections of every elf on one line, usually a pair.
+type Assignment = Vec<Interval>;
 
-fn parse_file(input: &str) -> nom::IResult<&str, Vec<Assignment>> {
-    separated_list1(line_ending, parse_line)(input)
+fn parse_file(input: &str) -> ParseResult<'_, Vec<Assignment>> {
+    lines(parse_line)(input)
 }
 
-fn parse_line(input: &str) -> nom::IResult<&str, Assignment> {
+fn parse_line(input: &str) -> ParseResult<'_, Assignment> {
     nom::combinator::map(
-        tuple((parse_group, tag(","), parse_group)),
-        |(first, _, second)| (first, second),
+        pair(parse_group, many1(preceded(token(","), parse_group))),
+        |(first, mut rest)| {
+            rest.insert(0, first);
+            rest
+        },
     )(input)
 }
 
-fn parse_group(input: &str) -> nom::IResult<&str, RangeInclusive<u64>> {
-    nom::combinator::map(tuple((integer, tag("-"), integer)), |(start, _, end)| {
-        start..=end
-    })(input)
+fn parse_group(input: &str) -> ParseResult<'_, Interval> {
+    context(
+        "section range",
+        map_opt(tuple((integer, token("-"), integer)), |(start, _, end)| {
+            Interval::new(start, end)
+        }),
+    )(input)
 }
 
-fn fully_contains(a: &RangeInclusive<u64>, b: &RangeInclusive<u64>) -> bool {
-    a.start() <= b.start() && a.end() >= b.end()
+/// Some elf's sections hold all the sections of another elf.
+fn any_contained(assignment: &[Interval]) -> bool {
+    assignment
+        .iter()
+        .tuple_combinations()
+        .any(|(a, b)| a.contains(b) || b.contains(a))
+}
+
+fn any_overlapping(assignment: &[Interval]) -> bool {
+    assignment
+        .iter()
+        .tuple_combinations()
+        .any(|(a, b)| a.overlaps(b))
+}
+
+pub struct Day4 {
+    assignments: Vec<Assignment>,
+}
+
+impl Day4 {
+    fn fully_contained_count(&self) -> u64 {
+        self.assignments
+            .par_iter()
+            .filter(|assignment| any_contained(assignment))
+            .count() as u64
+    }
+
+    fn overlapping_count(&self) -> u64 {
+        self.assignments
+            .par_iter()
+            .filter(|assignment| any_overlapping(assignment))
+            .count() as u64
+    }
+
+    /// Sections more than `k` elves are assigned to, across all lines.
+    pub fn sections_covered_more_than(&self, k: usize) -> Vec<Interval> {
+        interval::covered_more_than(self.assignments.iter().flatten().copied(), k)
+    }
 }
 
-fn overlaps(a: &RangeInclusive<u64>, b: &RangeInclusive<u64>) -> bool {
-    a.start() <= b.end() && b.start() <= a.end()
+impl Solution for Day4 {
+    fn parse<R>(input: R) -> AocResult<Self>
+    where
+        R: BufRead,
+    {
+        let input = utils::read_input_string(input)?;
+        Ok(Day4 {
+            assignments: parse_complete(&input, parse_file, EXPECTED)?,
+        })
+    }
+
+    fn part_one(&self) -> AocResult<Answer> {
+        Ok(self.fully_contained_count().into())
+    }
+
+    fn part_two(&self) -> AocResult<Answer> {
+        Ok(self.overlapping_count().into())
+    }
 }
 
-pub fn task_a<P>(file: P) -> io::Result<u64>
+impl Streaming for Day4 {
+    fn stream<R>(input: R) -> AocResult<[Answer; 2]>
+    where
+        R: BufRead,
+    {
+        let mut contained = 0u64;
+        let mut overlapping = 0u64;
+        for_each_record(input, parse_line, EXPECTED, |assignment| {
+            contained += u64::from(any_contained(&assignment));
+            overlapping += u64::from(any_overlapping(&assignment));
+            Ok(())
+        })?;
+        Ok([contained.into(), overlapping.into()])
+    }
+}
+
+impl Generate for Day4 {
+    fn generate<R>(rng: &mut R, size: usize) -> String
+    where
+        R: Rng,
+    {
+        let mut range = |rng: &mut R| {
+            let start = rng.gen_range(1..=99);
+            format!("{}-{}", start, rng.gen_range(start..=99))
+        };
+        (0..size.max(1))
+            .map(|_| format!("{},{}\n", range(rng), range(rng)))
+            .collect()
+    }
+}
+
+pub fn task_a<P>(file: P) -> AocResult<u64>
 where
     P: AsRef<Path>,
 {
-    let input = utils::get_input_string(file)?;
-    let (_, assignments) = parse_file(&input).unwrap();
-    Ok(assignments
-        .par_iter()
-        .map(|(first, second)| fully_contains(first, second) || fully_contains(second, first))
-        .filter(|b| *b)
-        .count() as u64)
+    Ok(Day4::parse_file(file)?.fully_contained_count())
 }
 
-pub fn task_b<P>(file: P) -> io::Result<u64>
+pub fn task_b<P>(file: P) -> AocResult<u64>
 where
     P: AsRef<Path>,
 {
-    let input = utils::get_input_string(file)?;
-    let (_, assignments) = parse_file(&input).unwrap();
-    Ok(assignments
-        .par_iter()
-        .map(|(first, second)| overlaps(first, second))
-        .filter(|b| *b)
-        .count() as u64)
+    Ok(Day4::parse_file(file)?.overlapping_count())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::AocError;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use std::collections::HashSet;
 
     #[test]
     fn test_a_works() {
@@ -90,4 +171,79 @@ mod tests {
         let result = task_b("data/day4t.txt").unwrap();
         assert_eq!(result, 4)
     }
+
+    #[test]
+    fn malformed_line_is_reported() {
+        let result = Day4::parse_str("2-4,6-8\n2-3;4-5\n");
+        assert!(matches!(
+            result,
+            Err(AocError::Parse {
+                line: 2,
+                column: 4,
+                ..
+            })
+        ));
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "Parse error at line 2, column 4: expected \",\"\n2 | 2-3;4-5\n  |    ^"
+        );
+    }
+
+    #[test]
+    fn lines_can_hold_many_elves() {
+        let input = "2-4,6-8,3-3\n1-2,4-5,7-9\n5-9,1-5\n";
+        let day = Day4::parse_str(input).unwrap();
+        assert_eq!(day.part_one().unwrap(), Answer::Number(1));
+        assert_eq!(day.part_two().unwrap(), Answer::Number(2));
+        assert_eq!(
+            Day4::stream(input.as_bytes()).unwrap(),
+            [1u64.into(), 2u64.into()]
+        );
+        let busy = day
+            .sections_covered_more_than(2)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(busy, ["2-5", "7-8"]);
+        assert_eq!(
+            Day4::parse_str("2-4,8-6\n").err().unwrap().to_string(),
+            "Parse error at line 1, column 5: expected section range\n1 | 2-4,8-6\n  |     ^"
+        );
+    }
+
+    /// Two sections as start and length.
+    type Pair = ((u64, u64), (u64, u64));
+
+    /// Spells every section out as a set of points.
+    fn reference(pairs: &[Pair]) -> (u64, u64) {
+        let sections =
+            |(start, length): (u64, u64)| (start..=start + length).collect::<HashSet<_>>();
+        let mut contained = 0;
+        let mut overlapping = 0;
+        for &(first, second) in pairs {
+            let (first, second) = (sections(first), sections(second));
+            if first.is_subset(&second) || second.is_subset(&first) {
+                contained += 1;
+            }
+            if !first.is_disjoint(&second) {
+                overlapping += 1;
+            }
+        }
+        (contained, overlapping)
+    }
+
+    proptest! {
+        #[test]
+        fn matches_reference(pairs in vec(((1..60u64, 0..20u64), (1..60u64, 0..20u64)), 1..40)) {
+            let input = pairs
+                .iter()
+                .map(|((a, a_len), (b, b_len))| format!("{}-{},{}-{}\n", a, a + a_len, b, b + b_len))
+                .collect::<String>();
+            let (contained, overlapping) = reference(&pairs);
+            let day = Day4::parse_str(&input).unwrap();
+            prop_assert_eq!(day.part_one().unwrap(), contained.into());
+            prop_assert_eq!(day.part_two().unwrap(), overlapping.into());
+            prop_assert_eq!(Day4::stream(input.as_bytes()).unwrap(), [contained.into(), overlapping.into()]);
+        }
+    }
 }
@@ -1,24 +1,26 @@
-use crate::parsers::integer;
+use crate::error::{AocError, AocResult};
+use crate::generate::{Generate, LETTERS};
+use crate::parsers::{integer, lines, token, whitespace_list, ParseResult};
+use crate::solution::{Answer, Solution};
 use crate::utils;
 use itertools::Itertools;
 use nom::branch::alt;
-use nom::bytes::complete::tag;
-use nom::character::complete::{alpha1, digit1, line_ending, multispace0, space0};
+use nom::character::complete::{alpha1, digit1, multispace0, space0};
 use nom::combinator::{map_res, opt};
 use nom::error::dbg_dmp;
 use nom::multi::separated_list1;
 use nom::sequence::{delimited, tuple};
+use rand::seq::SliceRandom;
+use rand::Rng;
 use std::cell::RefCell;
 use std::collections::VecDeque;
 use std::fmt::{Display, Formatter};
 use std::fs::File;
-use std::io;
-use std::io::BufReader;
+use std::io::{BufRead, BufReader};
 use std::path::Path;
-use std::str::FromStr;
 
-type CrateStack<'a> = VecDeque<Crate<'a>>;
-type Stacks<'a> = Vec<RefCell<CrateStack<'a>>>;
+type CrateStack = VecDeque<Crate>;
+type Stacks = Vec<RefCell<CrateStack>>;
 
 struct Movement {
     quantity: u64,
@@ -26,32 +28,60 @@ struct Movement {
     to: u64,
 }
 
+fn get_stack(stacks: &Stacks, index: u64) -> AocResult<&RefCell<CrateStack>> {
+    index
+        .checked_sub(1)
+        .and_then(|i| stacks.get(i as usize))
+        .ok_or(AocError::UnknownStack(index))
+}
+
 impl Movement {
     fn new(quantity: u64, from: u64, to: u64) -> Self {
         Self { quantity, from, to }
     }
 
-    fn execute(&self, stacks: &Stacks) {
-        let mut from_stack = stacks.get((self.from - 1) as usize).unwrap().borrow_mut();
-        let mut to_stack = stacks.get((self.to - 1) as usize).unwrap().borrow_mut();
+    fn check(&self, stacks: &Stacks) -> AocResult<()> {
+        get_stack(stacks, self.to)?;
+        if get_stack(stacks, self.from)?.borrow().len() < self.quantity as usize {
+            return Err(AocError::EmptyStack(self.from));
+        }
+        Ok(())
+    }
+
+    fn execute(&self, stacks: &Stacks) -> AocResult<()> {
+        self.check(stacks)?;
+        if self.from == self.to {
+            return Ok(());
+        }
+        let mut from_stack = get_stack(stacks, self.from)?.borrow_mut();
+        let mut to_stack = get_stack(stacks, self.to)?.borrow_mut();
         for i in 0..self.quantity {
-            let moved_crate = from_stack.pop_front().unwrap();
+            let moved_crate = from_stack
+                .pop_front()
+                .ok_or(AocError::EmptyStack(self.from))?;
             to_stack.push_front(moved_crate);
         }
+        Ok(())
     }
 
-    fn execute_task_b(&self, stacks: &Stacks) {
-        let mut from_stack = stacks.get((self.from - 1) as usize).unwrap().borrow_mut();
+    fn execute_task_b(&self, stacks: &Stacks) -> AocResult<()> {
+        self.check(stacks)?;
+        if self.from == self.to {
+            return Ok(());
+        }
+        let mut from_stack = get_stack(stacks, self.from)?.borrow_mut();
         let mut temp = VecDeque::<Crate>::with_capacity(self.quantity as usize);
         for i in 0..self.quantity {
-            let moved_crate = from_stack.pop_front().unwrap();
+            let moved_crate = from_stack
+                .pop_front()
+                .ok_or(AocError::EmptyStack(self.from))?;
             temp.push_back(moved_crate);
         }
-        let mut to_stack = stacks.get((self.to - 1) as usize).unwrap().borrow_mut();
-        for i in 0..self.quantity {
-            let moved_crate = temp.pop_back().unwrap();
+        let mut to_stack = get_stack(stacks, self.to)?.borrow_mut();
+        while let Some(moved_crate) = temp.pop_back() {
             to_stack.push_front(moved_crate);
         }
+        Ok(())
     }
 }
 
@@ -61,22 +91,23 @@ impl Display for Movement {
     }
 }
 
-struct Crate<'a> {
-    name: &'a str,
+#[derive(Clone)]
+struct Crate {
+    name: String,
 }
 
-fn parse_all_cmds(input: &str) -> nom::IResult<&str, Vec<Movement>> {
-    separated_list1(line_ending, parse_movement_command)(input)
+fn parse_all_cmds(input: &str) -> ParseResult<'_, Vec<Movement>> {
+    lines(parse_movement_command)(input)
 }
 
-fn parse_movement_command(input: &str) -> nom::IResult<&str, Movement> {
+fn parse_movement_command(input: &str) -> ParseResult<'_, Movement> {
     nom::combinator::map(
         tuple((
-            tag("move "),
+            token("move "),
             integer,
-            tag(" from "),
+            token(" from "),
             integer,
-            tag(" to "),
+            token(" to "),
             integer,
         )),
         |(_, quantity, _, from, _, to)| Movement::new(quantity, from, to),
@@ -84,91 +115,193 @@ fn parse_movement_command(input: &str) -> nom::IResult<&str, Movement> {
 }
 
 fn parse_stacks_number(input: &str) -> Option<u64> {
-    input
-        .split(" ")
-        .map(|int| u64::from_str(int).unwrap_or(0))
-        .max()
+    whitespace_list(integer)(input)
+        .ok()
+        .and_then(|(_, numbers)| numbers.into_iter().max())
 }
 
-fn parse_empty_crate(input: &str) -> nom::IResult<&str, Option<Crate>> {
-    nom::combinator::map(tag("   "), |_| None)(input)
+fn parse_empty_crate(input: &str) -> ParseResult<'_, Option<Crate>> {
+    nom::combinator::map(token("   "), |_| None)(input)
 }
 
-fn parse_crate(input: &str) -> nom::IResult<&str, Option<Crate>> {
-    nom::combinator::map(delimited(tag("["), alpha1, tag("]")), |name| {
-        Some(Crate { name })
+fn parse_crate(input: &str) -> ParseResult<'_, Option<Crate>> {
+    nom::combinator::map(delimited(token("["), alpha1, token("]")), |name: &str| {
+        Some(Crate {
+            name: name.to_string(),
+        })
     })(input)
 }
 
-fn parse_crate_line(input: &str) -> nom::IResult<&str, Vec<Option<Crate>>> {
-    separated_list1(tag(" "), alt((parse_crate, parse_empty_crate)))(input)
+fn parse_crate_line(input: &str) -> ParseResult<'_, Vec<Option<Crate>>> {
+    separated_list1(token(" "), alt((parse_crate, parse_empty_crate)))(input)
+}
+
+pub struct Day5 {
+    stacks: Vec<CrateStack>,
+    commands: Vec<Movement>,
+}
+
+impl Day5 {
+    fn rearrange<F>(&self, execute: F) -> AocResult<String>
+    where
+        F: Fn(&Movement, &Stacks) -> AocResult<()>,
+    {
+        let stacks = self
+            .stacks
+            .iter()
+            .cloned()
+            .map(RefCell::new)
+            .collect::<Stacks>();
+        for cmd in &self.commands {
+            execute(cmd, &stacks)?;
+        }
+        Ok(stacks
+            .iter()
+            .filter_map(|stack| stack.borrow().front().map(|top| top.name.clone()))
+            .join(""))
+    }
+}
+
+impl Solution for Day5 {
+    fn parse<R>(input: R) -> AocResult<Self>
+    where
+        R: BufRead,
+    {
+        Self::parse_str(&utils::read_input_string(input)?)
+    }
+
+    fn parse_str(input: &str) -> AocResult<Self> {
+        let mut input_lines = input.split("\n\n");
+        let crates = input_lines
+            .next()
+            .ok_or(AocError::MissingSection("stacks"))?;
+        let mut crates_iter = crates.lines().rev();
+        let stacks_line = crates_iter
+            .next()
+            .ok_or(AocError::MissingSection("stacks"))?;
+        let crates_num = parse_stacks_number(stacks_line).unwrap_or(0);
+        let mut stacks = (0..crates_num)
+            .map(|_| CrateStack::new())
+            .collect::<Vec<_>>();
+        for stack_line in crates_iter {
+            let (rest, crates) =
+                parse_crate_line(stack_line).map_err(|e| AocError::from_nom(input, e))?;
+            if !rest.trim().is_empty() {
+                return Err(AocError::parse_at(
+                    input,
+                    rest.trim_start(),
+                    "expected crate",
+                ));
+            }
+            for (i, cur_crate) in crates.into_iter().enumerate() {
+                if let Some(cur_crate) = cur_crate {
+                    stacks
+                        .get_mut(i)
+                        .ok_or(AocError::UnknownStack(i as u64 + 1))?
+                        .push_front(cur_crate);
+                }
+            }
+        }
+        let commands = input_lines
+            .next()
+            .ok_or(AocError::MissingSection("moves"))?;
+        if commands.trim().is_empty() {
+            return Ok(Day5 {
+                stacks,
+                commands: vec![],
+            });
+        }
+        let (rest, commands) =
+            parse_all_cmds(commands).map_err(|e| AocError::from_nom(input, e))?;
+        let rest = rest.trim_start();
+        if !rest.is_empty() {
+            return Err(AocError::parse_at(input, rest, "expected move command"));
+        }
+        Ok(Day5 { stacks, commands })
+    }
+
+    fn part_one(&self) -> AocResult<Answer> {
+        Ok(self.rearrange(Movement::execute)?.into())
+    }
+
+    fn part_two(&self) -> AocResult<Answer> {
+        Ok(self.rearrange(Movement::execute_task_b)?.into())
+    }
+}
+
+impl Generate for Day5 {
+    /// Draws up to nine stacks followed by `size` moves that are all legal when executed.
+    fn generate<R>(rng: &mut R, size: usize) -> String
+    where
+        R: Rng,
+    {
+        let count = rng.gen_range(3..=9);
+        let stacks = (0..count)
+            .map(|_| {
+                let height = rng.gen_range(1..=8);
+                (0..height)
+                    .map(|_| *LETTERS[26..].choose(rng).unwrap() as char)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let top = stacks.iter().map(Vec::len).max().unwrap_or(0);
+        let mut input = String::new();
+        for row in (0..top).rev() {
+            let line = stacks
+                .iter()
+                .map(|stack| match stack.get(row) {
+                    Some(name) => format!("[{}]", name),
+                    None => "   ".to_string(),
+                })
+                .join(" ");
+            input.push_str(&line);
+            input.push('\n');
+        }
+        input.push_str(&(1..=count).map(|i| format!(" {} ", i)).join(" "));
+        input.push_str("\n\n");
+
+        let mut heights = stacks.iter().map(Vec::len).collect::<Vec<_>>();
+        for _ in 0..size {
+            let filled = (0..count).filter(|&i| heights[i] > 0).collect::<Vec<_>>();
+            let from = *filled.choose(rng).unwrap();
+            let to = *(0..count)
+                .filter(|&i| i != from)
+                .collect::<Vec<_>>()
+                .choose(rng)
+                .unwrap();
+            let quantity = rng.gen_range(1..=heights[from]);
+            heights[from] -= quantity;
+            heights[to] += quantity;
+            input.push_str(&format!(
+                "move {} from {} to {}\n",
+                quantity,
+                from + 1,
+                to + 1
+            ));
+        }
+        input
+    }
 }
 
-pub fn task_a<P>(file: P) -> io::Result<String>
+pub fn task_a<P>(file: P) -> AocResult<String>
 where
     P: AsRef<Path>,
 {
-    let input = utils::get_input_string(file)?;
-    let mut input_lines = input.split("\n\n");
-    let crates = input_lines.next().unwrap();
-    let mut crates_iter = crates.lines().rev();
-    let stacks_line = crates_iter.next().unwrap();
-    let crates_num = parse_stacks_number(stacks_line).unwrap();
-    let mut stacks = (0..crates_num)
-        .map(|_| RefCell::new(CrateStack::new()))
-        .collect::<Vec<_>>();
-    for stack_line in crates_iter {
-        let crates = parse_crate_line(stack_line).unwrap().1;
-        crates
-            .into_iter()
-            .enumerate()
-            .filter(|maybe_crate| maybe_crate.1.is_some())
-            .for_each(|(i, cur_crate)| stacks[i].borrow_mut().push_front(cur_crate.unwrap()));
-    }
-    let commands = input_lines.next().unwrap();
-    let parsed_commands = parse_all_cmds(commands).unwrap().1;
-    parsed_commands.iter().for_each(|cmd| cmd.execute(&stacks));
-    Ok(stacks
-        .iter()
-        .map(|stack| stack.borrow().front().unwrap().name)
-        .join(""))
-}
-
-pub fn task_b<P>(file: P) -> io::Result<String>
+    Day5::parse_file(file)?.rearrange(Movement::execute)
+}
+
+pub fn task_b<P>(file: P) -> AocResult<String>
 where
     P: AsRef<Path>,
 {
-    let input = utils::get_input_string(file)?;
-    let mut input_lines = input.split("\n\n");
-    let crates = input_lines.next().unwrap();
-    let mut crates_iter = crates.lines().rev();
-    let stacks_line = crates_iter.next().unwrap();
-    let crates_num = parse_stacks_number(stacks_line).unwrap();
-    let mut stacks = (0..crates_num)
-        .map(|_| RefCell::new(CrateStack::new()))
-        .collect::<Vec<_>>();
-    for stack_line in crates_iter {
-        let crates = parse_crate_line(stack_line).unwrap().1;
-        crates
-            .into_iter()
-            .enumerate()
-            .filter(|maybe_crate| maybe_crate.1.is_some())
-            .for_each(|(i, cur_crate)| stacks[i].borrow_mut().push_front(cur_crate.unwrap()));
-    }
-    let commands = input_lines.next().unwrap();
-    let parsed_commands = parse_all_cmds(commands).unwrap().1;
-    parsed_commands
-        .iter()
-        .for_each(|cmd| cmd.execute_task_b(&stacks));
-    Ok(stacks
-        .iter()
-        .map(|stack| stack.borrow().front().unwrap().name)
-        .join(""))
+    Day5::parse_file(file)?.rearrange(Movement::execute_task_b)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generate;
+    use proptest::prelude::*;
 
     #[test]
     fn test_a_works() {
@@ -181,4 +314,52 @@ mod tests {
         let result = task_b("data/day5t.txt").unwrap();
         assert_eq!(result, "MCD")
     }
+
+    #[test]
+    fn move_from_empty_stack_is_reported() {
+        let input = "[A]    \n 1   2   3 \n\nmove 1 from 3 to 1";
+        let result = Day5::parse_str(input).unwrap().part_one();
+        assert!(matches!(result, Err(AocError::EmptyStack(3))));
+    }
+
+    /// Reads the drawing column by column and moves crates one at a time, or in one slice when
+    /// `at_once`.
+    fn reference(input: &str, at_once: bool) -> String {
+        let (drawing, moves) = input.split_once("\n\n").unwrap();
+        let rows = drawing.lines().collect::<Vec<_>>();
+        let count = rows.last().unwrap().split_whitespace().count();
+        let mut stacks = vec![vec![]; count];
+        for row in rows[..rows.len() - 1].iter().rev() {
+            for (i, stack) in stacks.iter_mut().enumerate() {
+                match row.chars().nth(1 + 4 * i) {
+                    Some(name) if name != ' ' => stack.push(name),
+                    _ => {}
+                }
+            }
+        }
+        for line in moves.lines() {
+            let numbers = line
+                .split_whitespace()
+                .filter_map(|word| word.parse::<usize>().ok())
+                .collect::<Vec<_>>();
+            let (quantity, from, to) = (numbers[0], numbers[1] - 1, numbers[2] - 1);
+            let keep = stacks[from].len() - quantity;
+            let mut moved = stacks[from].split_off(keep);
+            if !at_once {
+                moved.reverse();
+            }
+            stacks[to].extend(moved);
+        }
+        stacks.iter().filter_map(|stack| stack.last()).collect()
+    }
+
+    proptest! {
+        #[test]
+        fn matches_reference(seed in any::<u64>(), moves in 0..40usize) {
+            let input = generate::seeded::<Day5>(seed, moves);
+            let day = Day5::parse_str(&input).unwrap();
+            prop_assert_eq!(day.part_one().unwrap(), reference(&input, false).into());
+            prop_assert_eq!(day.part_two().unwrap(), reference(&input, true).into());
+        }
+    }
 }
@@ -1,59 +1,246 @@
+use crate::error::{AocError, AocResult};
+use crate::generate::{distinct_letters, letters_from, Generate, LETTERS};
+use crate::solution::{Answer, Solution};
+use crate::streaming::Streaming;
 use crate::utils;
 use itertools::Itertools;
+use rand::Rng;
 use std::collections::BTreeSet;
 use std::fs::File;
-use std::io;
-use std::io::BufReader;
+use std::io::{self, BufRead, BufReader};
 use std::path::Path;
 
-fn find_marker(signal: &str, unique_len: usize) -> usize {
-    for i in signal.char_indices().skip(unique_len) {
-        let substring = &signal[(i.0 - unique_len)..i.0];
-        let substring_chars = substring.chars().collect::<BTreeSet<_>>();
-        if substring.len() == substring_chars.len() {
-            return i.0;
+/// The signal is checked to be ASCII when parsed, so every byte is a character.
+fn find_marker(signal: &str, unique_len: usize) -> AocResult<usize> {
+    signal
+        .as_bytes()
+        .windows(unique_len)
+        .position(|window| window.iter().collect::<BTreeSet<_>>().len() == unique_len)
+        .map(|start| start + unique_len)
+        .ok_or(AocError::NoMarker(unique_len))
+}
+
+pub struct Day6 {
+    signal: String,
+}
+
+impl Solution for Day6 {
+    fn parse<R>(input: R) -> AocResult<Self>
+    where
+        R: BufRead,
+    {
+        Self::parse_str(&utils::read_input_string(input)?)
+    }
+
+    /// The signal is the first line.
+    fn parse_str(input: &str) -> AocResult<Self> {
+        let signal = input.lines().next().unwrap_or_default();
+        if let Some(c) = signal.chars().find(|c| !c.is_ascii()) {
+            return Err(AocError::NotAsciiSymbol(c));
+        }
+        Ok(Day6 {
+            signal: signal.to_string(),
+        })
+    }
+
+    fn part_one(&self) -> AocResult<Answer> {
+        Ok(find_marker(&self.signal, 4)?.into())
+    }
+
+    fn part_two(&self) -> AocResult<Answer> {
+        Ok(find_marker(&self.signal, 14)?.into())
+    }
+}
+
+/// Tracks where the newest run of distinct characters starts, so a marker is found without
+/// looking back at the signal.
+struct MarkerSearch {
+    length: usize,
+    start: usize,
+    found: Option<usize>,
+}
+
+impl MarkerSearch {
+    fn new(length: usize) -> Self {
+        MarkerSearch {
+            length,
+            start: 0,
+            found: None,
+        }
+    }
+
+    /// `previous` is where the character at `position` was last seen.
+    fn push(&mut self, position: usize, previous: Option<usize>) {
+        if self.found.is_some() {
+            return;
+        }
+        if let Some(previous) = previous {
+            self.start = self.start.max(previous + 1);
+        }
+        if position + 1 - self.start >= self.length {
+            self.found = Some(position + 1);
+        }
+    }
+
+    fn answer(&self) -> AocResult<Answer> {
+        Ok(self.found.ok_or(AocError::NoMarker(self.length))?.into())
+    }
+}
+
+/// The error for a signal holding the non-ASCII `first` byte, decoding the whole character.
+fn non_ascii<I>(first: u8, bytes: &mut I) -> AocError
+where
+    I: Iterator<Item = io::Result<u8>>,
+{
+    let width = match first {
+        0xF0.. => 4,
+        0xE0.. => 3,
+        _ => 2,
+    };
+    let mut encoded = vec![first];
+    for byte in bytes.take(width - 1) {
+        match byte {
+            Ok(byte) => encoded.push(byte),
+            Err(error) => return error.into(),
         }
     }
-    0
+    match std::str::from_utf8(&encoded) {
+        Ok(text) => AocError::NotAsciiSymbol(text.chars().next().unwrap()),
+        Err(error) => io::Error::new(io::ErrorKind::InvalidData, error).into(),
+    }
+}
+
+impl Streaming for Day6 {
+    /// Reads the first line byte by byte. Once both markers are found the rest of the line is
+    /// only checked to be ASCII, like `parse` does.
+    fn stream<R>(input: R) -> AocResult<[Answer; 2]>
+    where
+        R: BufRead,
+    {
+        let mut last_seen = [None; 128];
+        let mut packet = MarkerSearch::new(4);
+        let mut message = MarkerSearch::new(14);
+        let mut bytes = input.bytes();
+        let mut position = 0;
+        while let Some(byte) = bytes.next() {
+            let byte = byte?;
+            if byte == b'\n' || byte == b'\r' {
+                break;
+            }
+            if !byte.is_ascii() {
+                return Err(non_ascii(byte, &mut bytes));
+            }
+            if message.found.is_none() {
+                let previous = last_seen[byte as usize].replace(position);
+                packet.push(position, previous);
+                message.push(position, previous);
+            }
+            position += 1;
+        }
+        Ok([packet.answer()?, message.answer()?])
+    }
 }
 
-pub fn task_a<P>(file: P) -> io::Result<String>
+impl Generate for Day6 {
+    /// Plants a start-of-packet marker and later a start-of-message marker in filler that is too
+    /// repetitive to contain either on its own.
+    fn generate<R>(rng: &mut R, size: usize) -> String
+    where
+        R: Rng,
+    {
+        let size = size.max(30);
+        let lowercase = &LETTERS[..26];
+        let packet_at = rng.gen_range(3..size / 3);
+        let message_at = rng.gen_range(1..size / 3);
+        let mut signal = letters_from(rng, &['a', 'b', 'c'], packet_at);
+        signal.extend(distinct_letters(rng, &lowercase[3..], 4));
+        signal += &letters_from(rng, &['a', 'b', 'c', 'd', 'e', 'f'], message_at);
+        signal.extend(distinct_letters(rng, lowercase, 14));
+        let tail = size.saturating_sub(signal.len()).max(1);
+        signal += &letters_from(rng, &['a', 'b', 'c'], tail);
+        signal + "\n"
+    }
+}
+
+pub fn task_a<P>(file: P) -> AocResult<String>
 where
     P: AsRef<Path>,
 {
-    let input = utils::get_input_string(file)?;
-    Ok(find_marker(&input, 4).to_string())
+    Ok(Day6::parse_file(file)?.part_one()?.to_string())
 }
 
-pub fn task_b<P>(file: P) -> io::Result<String>
+pub fn task_b<P>(file: P) -> AocResult<String>
 where
     P: AsRef<Path>,
 {
-    let input = utils::get_input_string(file)?;
-    Ok(find_marker(&input, 14).to_string())
+    Ok(Day6::parse_file(file)?.part_two()?.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn find_marker_works() {
-        assert_eq!(find_marker("bvwbjplbgvbhsrlpgdmjqwftvncz", 4), 5);
-        assert_eq!(find_marker("nppdvjthqldpwncqszvftbrmjlhg", 4), 6);
-        assert_eq!(find_marker("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 14), 19);
-        assert_eq!(find_marker("bvwbjplbgvbhsrlpgdmjqwftvncz", 14), 23);
+        assert_eq!(find_marker("bvwbjplbgvbhsrlpgdmjqwftvncz", 4).unwrap(), 5);
+        assert_eq!(find_marker("nppdvjthqldpwncqszvftbrmjlhg", 4).unwrap(), 6);
+        assert_eq!(
+            find_marker("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 14).unwrap(),
+            19
+        );
+        assert_eq!(find_marker("bvwbjplbgvbhsrlpgdmjqwftvncz", 14).unwrap(), 23);
+    }
+
+    #[test]
+    fn bad_signals_are_reported() {
+        assert!(matches!(
+            Day6::parse_str("ééééabcd\n"),
+            Err(AocError::NotAsciiSymbol('é'))
+        ));
+        let day = Day6::parse_str("abab\n").unwrap();
+        assert_eq!(
+            day.part_one().unwrap_err().to_string(),
+            "No 4 distinct characters in a row"
+        );
+        for signal in ["ééééabcd\n", "abcdefghijklmnopqé\n"] {
+            assert!(matches!(
+                Day6::stream(signal.as_bytes()),
+                Err(AocError::NotAsciiSymbol('é'))
+            ));
+        }
     }
 
     #[test]
     fn test_a_works() {
-        // let result = task_a("data/day6t.txt").unwrap();
-        // assert_eq!(result, "")
+        let result = task_a("data/day6t.txt").unwrap();
+        assert_eq!(result, "7")
     }
 
     #[test]
     fn test_b_works() {
-        // let result = task_b("data/day6t.txt").unwrap();
-        // assert_eq!(result, "")
+        let result = task_b("data/day6t.txt").unwrap();
+        assert_eq!(result, "19")
+    }
+
+    /// Compares every pair inside every window.
+    fn reference_marker(signal: &str, unique_len: usize) -> Option<usize> {
+        let chars = signal.chars().collect::<Vec<_>>();
+        (unique_len..=chars.len()).find(|&end| {
+            let window = &chars[end - unique_len..end];
+            (0..unique_len).all(|i| (i + 1..unique_len).all(|j| window[i] != window[j]))
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn matches_reference(signal in "[a-p]{0,60}") {
+            let day = Day6::parse_str(&format!("{}\n", signal)).unwrap();
+            prop_assert_eq!(day.part_one().ok(), reference_marker(&signal, 4).map(Answer::from));
+            prop_assert_eq!(day.part_two().ok(), reference_marker(&signal, 14).map(Answer::from));
+            let streamed = Day6::stream(format!("{}\n", signal).as_bytes()).ok();
+            let expected = reference_marker(&signal, 4).zip(reference_marker(&signal, 14));
+            prop_assert_eq!(streamed, expected.map(|(packet, message)| [packet.into(), message.into()]));
+        }
     }
 }
@@ -1,29 +1,44 @@
-// use std::fs::File;
-// use std::io;
-// use std::io::BufReader;
-// use std::path::Path;
-//
-// pub fn task_a<P>(file: P) -> io::Result<String> where P: AsRef<Path> {
-//     unimplemented!()
-// }
-//
-// pub fn task_b<P>(file: P) -> io::Result<String> where P: AsRef<Path> {
-//     unimplemented!()
-// }
-//
-// #[cfg(test)]
-// mod tests {
-//     use super::*;
-//
-//     #[test]
-//     fn test_a_works() {
-//         let result = task_a("data/dayt.txt").unwrap();
-//         assert_eq!(result, 0)
-//     }
-//
-//     #[test]
-//     fn test_b_works() {
-//         let result = task_b("data/dayt.txt").unwrap();
-//         assert_eq!(result, 0)
-//     }
-// }
+//! Template for `aoc2022 new --day N`, which renames `DayN` and sets `DAY` in the copy.
+
+use crate::error::{AocError, AocResult};
+use crate::solution::{Answer, Solution};
+use crate::utils;
+use std::io::BufRead;
+
+pub const DAY: u8 = 0;
+
+pub struct DayN {
+    input: String,
+}
+
+impl Solution for DayN {
+    fn parse<R>(input: R) -> AocResult<Self>
+    where
+        R: BufRead,
+    {
+        Ok(DayN {
+            input: utils::read_input_string(input)?,
+        })
+    }
+
+    fn part_one(&self) -> AocResult<Answer> {
+        Err(AocError::Unsolved(DAY))
+    }
+
+    fn part_two(&self) -> AocResult<Answer> {
+        Err(AocError::Unsolved(DAY))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples;
+    use std::path::Path;
+
+    #[test]
+    fn examples_work() {
+        let result = examples::check_solution::<DayN>(Path::new("data"), DAY).unwrap();
+        assert_eq!(result, vec![])
+    }
+}
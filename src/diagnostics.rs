@@ -0,0 +1,115 @@
+use std::fmt::{Display, Formatter};
+
+use rayon::prelude::*;
+
+use crate::error::{AocError, AocResult};
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Mode {
+    /// Fail on the first rejected line.
+    #[default]
+    Strict,
+    /// Skip rejected lines and report them next to the answer.
+    Lenient,
+}
+
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub text: String,
+    pub error: AocError,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {} ({:?})", self.line, self.error, self.text)
+    }
+}
+
+#[derive(Debug)]
+pub struct Diagnosed<T> {
+    pub value: T,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl<T> Diagnosed<T> {
+    pub fn map<U, F>(self, f: F) -> Diagnosed<U>
+    where
+        F: FnOnce(T) -> U,
+    {
+        Diagnosed {
+            value: f(self.value),
+            diagnostics: self.diagnostics,
+        }
+    }
+}
+
+/// Applies `check` to every line, numbering lines from 1.
+///
+/// In strict mode the first rejected line becomes an `AocError::AtLine`, in lenient mode it is
+/// recorded as a diagnostic and left out of the values.
+pub fn check_lines<T, F>(lines: &[String], mode: Mode, check: F) -> AocResult<Diagnosed<Vec<T>>>
+where
+    T: Send,
+    F: Fn(&str) -> AocResult<T> + Sync,
+{
+    let results = lines
+        .par_iter()
+        .enumerate()
+        .map(|(i, line)| (i + 1, check(line)))
+        .collect::<Vec<_>>();
+    collect_checked(results, mode, |line| lines[line - 1].clone())
+}
+
+/// Splits results numbered by their 1-based input line into values and diagnostics.
+pub fn collect_checked<T, I, F>(results: I, mode: Mode, text: F) -> AocResult<Diagnosed<Vec<T>>>
+where
+    I: IntoIterator<Item = (usize, AocResult<T>)>,
+    F: Fn(usize) -> String,
+{
+    let mut value = vec![];
+    let mut diagnostics = vec![];
+    for (line, result) in results {
+        match (result, mode) {
+            (Ok(checked), _) => value.push(checked),
+            (Err(error), Mode::Strict) => return Err(AocError::at_line(line, error)),
+            (Err(error), Mode::Lenient) => diagnostics.push(Diagnostic {
+                line,
+                text: text(line),
+                error,
+            }),
+        }
+    }
+    Ok(Diagnosed { value, diagnostics })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines() -> Vec<String> {
+        vec!["1".to_string(), "x".to_string(), "3".to_string()]
+    }
+
+    fn check(line: &str) -> AocResult<u64> {
+        line.parse::<u64>()
+            .map_err(|_| AocError::InvalidFormat(line.to_string()))
+    }
+
+    #[test]
+    fn strict_mode_fails_with_line() {
+        let result = check_lines(&lines(), Mode::Strict, check);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Line 2: Invalid format string x"
+        );
+    }
+
+    #[test]
+    fn lenient_mode_collects_diagnostics() {
+        let result = check_lines(&lines(), Mode::Lenient, check).unwrap();
+        assert_eq!(result.value, vec![1, 3]);
+        assert_eq!(result.diagnostics.len(), 1);
+        assert_eq!(result.diagnostics[0].line, 2);
+    }
+}
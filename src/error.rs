@@ -0,0 +1,175 @@
+use std::io;
+
+use thiserror::Error;
+
+use crate::parsers::SyntaxError;
+use crate::solution::{Answer, Part};
+
+pub type AocResult<T> = Result<T, AocError>;
+
+#[derive(Error, Debug)]
+pub enum AocError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    #[error(transparent)]
+    ThreadPool(#[from] rayon::ThreadPoolBuildError),
+
+    #[error(
+        "Parse error at line {line}, column {column}: {message}{}",
+        show_source(*.line, .source_line, *.column)
+    )]
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+        source_line: String,
+    },
+
+    #[error("Line {line}: {source}")]
+    AtLine {
+        line: usize,
+        #[source]
+        source: Box<AocError>,
+    },
+
+    #[error("Unknown symbol {0}")]
+    UnknownSymbol(String),
+
+    #[error("Invalid format string {0}")]
+    InvalidFormat(String),
+
+    #[error("Invalid rules: {0}")]
+    InvalidRules(String),
+
+    #[error("Invalid packing, groups of {group_size} and {compartments} compartments")]
+    InvalidPacking {
+        group_size: usize,
+        compartments: usize,
+    },
+
+    #[error("Rucksack of {length} items can not be split into {compartments} compartments")]
+    UnevenCompartments { length: usize, compartments: usize },
+
+    #[error("Last group has only {found} of {expected} rucksacks")]
+    IncompleteGroup { found: usize, expected: usize },
+
+    #[error(
+        "Expected one shared item, found {}",
+        if .0.is_empty() { "none" } else { .0.as_str() }
+    )]
+    SharedItems(String),
+
+    #[error("No {0} distinct characters in a row")]
+    NoMarker(usize),
+
+    #[error("Not ASCII symbol {0:?}")]
+    NotAsciiSymbol(char),
+
+    #[error("Not a letter {0:?}")]
+    NotALetter(char),
+
+    #[error("Missing {0} section")]
+    MissingSection(&'static str),
+
+    #[error("There is no stack {0}")]
+    UnknownStack(u64),
+
+    #[error("Move from empty stack {0}")]
+    EmptyStack(u64),
+
+    #[error("AOC_SESSION is not set, day {0} can not be downloaded or submitted")]
+    MissingSession(u8),
+
+    #[error("Request to {url} failed: {message}")]
+    Http { url: String, message: String },
+
+    #[error("Day {0} is not solved yet")]
+    Unsolved(u8),
+
+    #[error("Day {0} already exists")]
+    DayExists(u8),
+
+    #[error("Refusing to submit {answer} for day {day} part {part}: {reason}")]
+    SubmissionRefused {
+        day: u8,
+        part: Part,
+        answer: Answer,
+        reason: String,
+    },
+}
+
+/// The offending line with a caret under `column`, or nothing when the line is unknown.
+fn show_source(line: usize, source_line: &str, column: usize) -> String {
+    if source_line.is_empty() {
+        return String::new();
+    }
+    let gutter = line.to_string();
+    let padding = source_line
+        .chars()
+        .take(column.saturating_sub(1))
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect::<String>();
+    format!(
+        "\n{} | {}\n{} | {}^",
+        gutter,
+        source_line,
+        " ".repeat(gutter.len()),
+        padding
+    )
+}
+
+impl AocError {
+    pub fn at_line(line: usize, error: AocError) -> Self {
+        AocError::AtLine {
+            line,
+            source: Box::new(error),
+        }
+    }
+
+    /// Builds a parse error pointing at `rest`, which must be a suffix slice of `input`.
+    pub fn parse_at<S>(input: &str, rest: &str, message: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let offset = (rest.as_ptr() as usize)
+            .saturating_sub(input.as_ptr() as usize)
+            .min(input.len());
+        let consumed = &input[..offset];
+        let line = consumed.matches('\n').count() + 1;
+        let line_start = consumed.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let source_line = input[line_start..].lines().next().unwrap_or("");
+        AocError::Parse {
+            line,
+            column: consumed[line_start..].chars().count() + 1,
+            message: message.into(),
+            source_line: source_line.trim_end_matches('\r').to_string(),
+        }
+    }
+
+    pub fn from_nom(input: &str, error: nom::Err<SyntaxError>) -> Self {
+        match error {
+            nom::Err::Incomplete(_) => {
+                AocError::parse_at(input, &input[input.len()..], "unexpected end of input")
+            }
+            nom::Err::Error(error) | nom::Err::Failure(error) => {
+                AocError::parse_at(input, error.input, format!("expected {}", error.expected))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_at_counts_lines_and_columns() {
+        let input = "2-4,6-8\n2-3,x-5\n";
+        let error = AocError::parse_at(input, &input[12..], "expected digit");
+        assert_eq!(
+            error.to_string(),
+            "Parse error at line 2, column 5: expected digit\n2 | 2-3,x-5\n  |     ^"
+        );
+    }
+}
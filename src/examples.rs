@@ -0,0 +1,220 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::error::{AocError, AocResult};
+use crate::registry::Day;
+use crate::solution::{Answer, Part, Solution};
+
+/// The example of a puzzle description: the sample input and the answer given for each part.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Example {
+    pub input: String,
+    pub answers: Vec<(Part, Answer)>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Mismatch {
+    pub day: u8,
+    pub part: Part,
+    pub expected: Answer,
+    pub actual: Answer,
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    unescape(&text)
+}
+
+fn between<'a>(html: &'a str, open: &str, close: &str) -> Vec<&'a str> {
+    let mut found = vec![];
+    let mut rest = html;
+    while let Some(start) = rest.find(open) {
+        rest = &rest[start + open.len()..];
+        match rest.find(close) {
+            Some(end) => {
+                found.push(&rest[..end]);
+                rest = &rest[end + close.len()..];
+            }
+            None => break,
+        }
+    }
+    found
+}
+
+/// Every puzzle states the example answer as the last emphasized code span of its part.
+fn article_answer(article: &str) -> Option<Answer> {
+    let mut spans = between(article, "<code><em>", "</em></code>");
+    spans.extend(between(article, "<em><code>", "</code></em>"));
+    spans
+        .into_iter()
+        .max_by_key(|span| span.as_ptr() as usize)
+        .map(|span| Answer::parse(strip_tags(span).trim()))
+}
+
+/// Extracts the first example block and the per-part answers from a saved puzzle page.
+pub fn extract_example(html: &str) -> AocResult<Example> {
+    let articles = between(html, "<article class=\"day-desc\">", "</article>");
+    let input = articles
+        .first()
+        .and_then(|article| {
+            between(article, "<pre><code>", "</code></pre>")
+                .into_iter()
+                .next()
+        })
+        .map(strip_tags)
+        .ok_or(AocError::MissingSection("example"))?;
+    let answers = [Part::A, Part::B]
+        .into_iter()
+        .zip(articles)
+        .filter_map(|(part, article)| article_answer(article).map(|answer| (part, answer)))
+        .collect();
+    Ok(Example { input, answers })
+}
+
+pub fn fixture_path(dir: &Path, day: u8) -> PathBuf {
+    dir.join(format!("day{}t.txt", day))
+}
+
+pub fn expected_path(dir: &Path, day: u8) -> PathBuf {
+    dir.join(format!("day{}t.expected", day))
+}
+
+/// Writes `dayNt.txt` and `dayNt.expected` (one `part answer` pair per line) into `dir`.
+pub fn write_fixture(dir: &Path, day: u8, example: &Example) -> AocResult<()> {
+    fs::create_dir_all(dir)?;
+    fs::write(fixture_path(dir, day), &example.input)?;
+    let expected = example
+        .answers
+        .iter()
+        .map(|(part, answer)| format!("{} {}\n", part, answer))
+        .collect::<String>();
+    fs::write(expected_path(dir, day), expected)?;
+    Ok(())
+}
+
+pub fn read_expected(path: &Path) -> AocResult<Vec<(Part, Answer)>> {
+    let expected = fs::read_to_string(path)?;
+    expected
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let (part, answer) = line
+                .split_once(' ')
+                .ok_or(AocError::InvalidFormat(line.to_string()))
+                .map_err(|e| AocError::at_line(i + 1, e))?;
+            let part = Part::from_str(part)
+                .map_err(|_| AocError::at_line(i + 1, AocError::UnknownSymbol(part.to_string())))?;
+            Ok((part, Answer::parse(answer.trim())))
+        })
+        .collect()
+}
+
+fn compare_example<F>(dir: &Path, day: u8, run: F) -> AocResult<Vec<Mismatch>>
+where
+    F: FnOnce(&[Part], &Path) -> AocResult<Vec<Answer>>,
+{
+    let expected_path = expected_path(dir, day);
+    if !expected_path.exists() {
+        return Ok(vec![]);
+    }
+    let (parts, expected): (Vec<_>, Vec<_>) = read_expected(&expected_path)?.into_iter().unzip();
+    let actual = run(&parts, &fixture_path(dir, day))?;
+    Ok(parts
+        .into_iter()
+        .zip(expected.into_iter().zip(actual))
+        .filter(|(_, (expected, actual))| expected != actual)
+        .map(|(part, (expected, actual))| Mismatch {
+            day,
+            part,
+            expected,
+            actual,
+        })
+        .collect())
+}
+
+/// Runs `day` on its example fixture, days without an expected answers file are skipped.
+pub fn check_example(dir: &Path, day: &Day) -> AocResult<Vec<Mismatch>> {
+    compare_example(dir, day.day, |parts, fixture| day.run(parts, fixture))
+}
+
+/// Same as `check_example` for a solution that is not registered yet.
+pub fn check_solution<S>(dir: &Path, day: u8) -> AocResult<Vec<Mismatch>>
+where
+    S: Solution,
+{
+    compare_example(dir, day, |parts, fixture| {
+        let solution = S::parse_file(fixture)?;
+        parts.iter().map(|part| solution.part(*part)).collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inputs::tests::temp_dir;
+    use crate::registry;
+
+    const PAGE: &str = r#"<main>
+<article class="day-desc"><h2>--- Day 4: Camp Cleanup ---</h2>
+<p>For example, consider the following list:</p>
+<pre><code>2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8
+</code></pre>
+<p>In how many pairs does one fully contain the other? Here: <code><em>2</em></code>.</p>
+</article>
+<p>Your puzzle answer was <code>540</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>In this example, the number of overlapping pairs is <code><em>4</em></code>.</p>
+</article>
+</main>"#;
+
+    #[test]
+    fn extract_example_works() {
+        let example = extract_example(PAGE).unwrap();
+        assert!(example.input.starts_with("2-4,6-8\n2-3,4-5\n"));
+        assert_eq!(
+            example.answers,
+            vec![(Part::A, Answer::Number(2)), (Part::B, Answer::Number(4))]
+        );
+        assert_eq!(unescape("&lt;x&gt; &amp;lt;"), "<x> &lt;");
+    }
+
+    #[test]
+    fn written_fixture_checks_out() {
+        let dir = temp_dir("examples");
+        write_fixture(&dir, 4, &extract_example(PAGE).unwrap()).unwrap();
+        let day = registry::get_day(4).unwrap();
+        assert_eq!(check_example(&dir, day).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn examples_match_expected() {
+        for day in registry::all_days() {
+            assert_eq!(check_example(Path::new("data"), day).unwrap(), vec![]);
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::error::{AocError, AocResult};
+
+/// How the rayon iterators inside every day are scheduled.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Execution {
+    /// A single-thread pool, so every parallel iterator runs in order on one thread.
+    Sequential,
+    /// A dedicated pool capped at the given number of threads.
+    Threads(usize),
+    /// The global rayon pool.
+    #[default]
+    Rayon,
+}
+
+impl Execution {
+    pub fn threads(&self) -> usize {
+        match self {
+            Execution::Sequential => 1,
+            Execution::Threads(threads) => *threads,
+            Execution::Rayon => rayon::current_num_threads(),
+        }
+    }
+
+    /// Runs `f` with every rayon call inside it scheduled by this strategy.
+    pub fn install<T, F>(&self, f: F) -> AocResult<T>
+    where
+        T: Send,
+        F: FnOnce() -> AocResult<T> + Send,
+    {
+        match self {
+            Execution::Rayon => f(),
+            Execution::Sequential | Execution::Threads(_) => rayon::ThreadPoolBuilder::new()
+                .num_threads(self.threads())
+                .build()?
+                .install(f),
+        }
+    }
+}
+
+impl FromStr for Execution {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sequential" => Ok(Execution::Sequential),
+            "rayon" => Ok(Execution::Rayon),
+            _ => match usize::from_str(s) {
+                Ok(threads) if threads > 0 => Ok(Execution::Threads(threads)),
+                _ => Err(AocError::UnknownSymbol(s.to_string())),
+            },
+        }
+    }
+}
+
+impl Display for Execution {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Execution::Sequential => write!(f, "sequential"),
+            Execution::Threads(threads) => write!(f, "{} threads", threads),
+            Execution::Rayon => write!(f, "rayon"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn execution_parses() {
+        assert_eq!(
+            Execution::from_str("sequential").unwrap(),
+            Execution::Sequential
+        );
+        assert_eq!(Execution::from_str("4").unwrap(), Execution::Threads(4));
+        assert!(Execution::from_str("0").is_err());
+    }
+
+    #[test]
+    fn install_caps_threads() {
+        let threads = Execution::Threads(2).install(|| Ok(rayon::current_num_threads()));
+        assert_eq!(threads.unwrap(), 2);
+        let threads = Execution::Sequential.install(|| Ok(rayon::current_num_threads()));
+        assert_eq!(threads.unwrap(), 1);
+    }
+}
@@ -0,0 +1,62 @@
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+pub const LETTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Days that can write valid random inputs for themselves.
+pub trait Generate {
+    /// Writes an input with roughly `size` records, whatever a record is for the day.
+    fn generate<R>(rng: &mut R, size: usize) -> String
+    where
+        R: Rng;
+}
+
+/// The same `seed` and `size` always give the same input.
+pub fn seeded<G>(seed: u64, size: usize) -> String
+where
+    G: Generate,
+{
+    G::generate(&mut ChaCha8Rng::seed_from_u64(seed), size)
+}
+
+/// `count` distinct letters picked from `pool`.
+pub fn distinct_letters<R>(rng: &mut R, pool: &[u8], count: usize) -> Vec<char>
+where
+    R: Rng,
+{
+    pool.choose_multiple(rng, count)
+        .map(|&letter| letter as char)
+        .collect()
+}
+
+pub fn letters_from<R>(rng: &mut R, pool: &[char], count: usize) -> String
+where
+    R: Rng,
+{
+    (0..count).filter_map(|_| pool.choose(rng)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry;
+    use crate::solution::Part;
+
+    #[test]
+    fn generated_inputs_solve() {
+        for day in registry::all_days().iter().filter(|day| day.day <= 6) {
+            for seed in 0..5 {
+                let input = day.generate(seed, 40).unwrap();
+                assert_eq!(input, day.generate(seed, 40).unwrap());
+                let answers = day.run_reader(&[Part::A, Part::B], &mut input.as_bytes());
+                assert!(
+                    answers.is_ok(),
+                    "day {} seed {}: {:?}",
+                    day.day,
+                    seed,
+                    answers
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{AocError, AocResult};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const DEFAULT_CACHE_DIR: &str = "data";
+
+pub trait HttpClient {
+    /// Performs a GET request authenticated with the `session` cookie and returns the body.
+    fn get(&self, url: &str, session: &str) -> AocResult<String>;
+
+    /// Posts an url-encoded form authenticated with the `session` cookie and returns the body.
+    fn post(&self, url: &str, session: &str, form: &[(&str, &str)]) -> AocResult<String>;
+}
+
+pub struct UreqClient {
+    agent: ureq::Agent,
+}
+
+impl Default for UreqClient {
+    fn default() -> Self {
+        UreqClient {
+            agent: ureq::AgentBuilder::new()
+                .user_agent("github.com/SalmonMoses/aoc2022")
+                .build(),
+        }
+    }
+}
+
+fn http_error<E>(url: &str, error: E) -> AocError
+where
+    E: ToString,
+{
+    AocError::Http {
+        url: url.to_string(),
+        message: error.to_string(),
+    }
+}
+
+impl HttpClient for UreqClient {
+    fn get(&self, url: &str, session: &str) -> AocResult<String> {
+        self.agent
+            .get(url)
+            .set("Cookie", &format!("session={}", session))
+            .call()
+            .map_err(|e| http_error(url, e))?
+            .into_string()
+            .map_err(|e| http_error(url, e))
+    }
+
+    fn post(&self, url: &str, session: &str, form: &[(&str, &str)]) -> AocResult<String> {
+        self.agent
+            .post(url)
+            .set("Cookie", &format!("session={}", session))
+            .send_form(form)
+            .map_err(|e| http_error(url, e))?
+            .into_string()
+            .map_err(|e| http_error(url, e))
+    }
+}
+
+/// Puzzle inputs stored as `dayN.txt` in a cache directory, downloaded once when missing.
+pub struct InputCache<C> {
+    dir: PathBuf,
+    base_url: String,
+    session: Option<String>,
+    client: C,
+}
+
+impl InputCache<UreqClient> {
+    /// Uses `AOC_CACHE_DIR` (defaulting to `data`) and the `AOC_SESSION` token.
+    pub fn from_env() -> Self {
+        let dir = env::var("AOC_CACHE_DIR").unwrap_or_else(|_| DEFAULT_CACHE_DIR.to_string());
+        let cache = InputCache::new(dir, UreqClient::default());
+        match env::var("AOC_SESSION") {
+            Ok(session) => cache.with_session(session.trim()),
+            Err(_) => cache,
+        }
+    }
+}
+
+impl<C> InputCache<C>
+where
+    C: HttpClient,
+{
+    pub fn new<P>(dir: P, client: C) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        InputCache {
+            dir: dir.into(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            session: None,
+            client,
+        }
+    }
+
+    pub fn with_session<S>(mut self, session: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.session = Some(session.into());
+        self
+    }
+
+    pub fn with_base_url<S>(mut self, base_url: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn with_dir<P>(mut self, dir: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.dir = dir.into();
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn path(&self, day: u8) -> PathBuf {
+        self.dir.join(format!("day{}.txt", day))
+    }
+
+    /// Returns the cached input of `day`, downloading it first if it is not cached yet.
+    pub fn resolve(&self, day: u8) -> AocResult<PathBuf> {
+        let path = self.path(day);
+        if path.exists() {
+            return Ok(path);
+        }
+        let session = self
+            .session
+            .as_deref()
+            .ok_or(AocError::MissingSession(day))?;
+        let url = format!("{}/2022/day/{}/input", self.base_url, day);
+        let input = self.client.get(&url, session)?;
+        fs::create_dir_all(&self.dir)?;
+        let partial_path = path.with_extension("txt.part");
+        fs::write(&partial_path, input)?;
+        fs::rename(&partial_path, &path)?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    pub(crate) fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc2022-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    /// Serves `body` to exactly one request and hands back the whole request.
+    pub(crate) fn serve_once(body: &'static str) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some(length) = line.to_lowercase().strip_prefix("content-length:") {
+                    content_length = length.trim().parse().unwrap();
+                }
+                request.push_str(&line);
+            }
+            let mut content = vec![0; content_length];
+            reader.read_exact(&mut content).unwrap();
+            request.push_str(&String::from_utf8(content).unwrap());
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+            request
+        });
+        (address, handle)
+    }
+
+    #[test]
+    fn resolve_downloads_once() {
+        let (address, server) = serve_once("1000\n2000\n");
+        let cache = InputCache::new(temp_dir("resolve"), UreqClient::default())
+            .with_base_url(address)
+            .with_session("secret");
+        let path = cache.resolve(1).unwrap();
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /2022/day/1/input"));
+        assert!(request.contains("session=secret"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1000\n2000\n");
+        // The stand-in server is gone, so a second download would fail.
+        assert_eq!(cache.resolve(1).unwrap(), path);
+    }
+
+    #[test]
+    fn resolve_requires_session() {
+        let cache = InputCache::new(temp_dir("session"), UreqClient::default());
+        assert!(matches!(cache.resolve(7), Err(AocError::MissingSession(7))));
+    }
+}
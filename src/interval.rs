@@ -0,0 +1,215 @@
+use std::fmt::{Display, Formatter};
+
+/// A closed range of sections with both ends included, never empty.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Interval {
+    start: u64,
+    end: u64,
+}
+
+impl Interval {
+    /// `None` when `end` comes before `start`.
+    pub fn new(start: u64, end: u64) -> Option<Self> {
+        (start <= end).then_some(Interval { start, end })
+    }
+
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    pub fn end(&self) -> u64 {
+        self.end
+    }
+
+    /// Number of sections, saturating for the one interval spanning every `u64`.
+    pub fn length(&self) -> u64 {
+        (self.end - self.start).saturating_add(1)
+    }
+
+    pub fn contains(&self, other: &Interval) -> bool {
+        self.start <= other.start && self.end >= other.end
+    }
+
+    pub fn contains_section(&self, section: u64) -> bool {
+        self.start <= section && section <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        Interval::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    /// Both intervals as one, `None` when there are sections between them.
+    pub fn union(&self, other: &Interval) -> Option<Interval> {
+        let touches =
+            self.start <= other.end.saturating_add(1) && other.start <= self.end.saturating_add(1);
+        touches.then(|| Interval {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        })
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+/// Disjoint intervals in order covering the same sections, touching intervals are joined.
+pub fn merge<I>(intervals: I) -> Vec<Interval>
+where
+    I: IntoIterator<Item = Interval>,
+{
+    let mut sorted = intervals.into_iter().collect::<Vec<_>>();
+    sorted.sort_unstable();
+    let mut merged: Vec<Interval> = vec![];
+    for interval in sorted {
+        match merged.last().and_then(|last| last.union(&interval)) {
+            Some(joined) => *merged.last_mut().unwrap() = joined,
+            None => merged.push(interval),
+        }
+    }
+    merged
+}
+
+/// The uncovered stretches between the first and the last covered section.
+pub fn gaps<I>(intervals: I) -> Vec<Interval>
+where
+    I: IntoIterator<Item = Interval>,
+{
+    merge(intervals)
+        .windows(2)
+        .map(|pair| Interval {
+            start: pair[0].end + 1,
+            end: pair[1].start - 1,
+        })
+        .collect()
+}
+
+/// Number of sections covered at least once.
+pub fn covered_length<I>(intervals: I) -> u64
+where
+    I: IntoIterator<Item = Interval>,
+{
+    merge(intervals).iter().map(Interval::length).sum()
+}
+
+/// Sections covered by more than `k` of the intervals, as disjoint intervals in order.
+pub fn covered_more_than<I>(intervals: I, k: usize) -> Vec<Interval>
+where
+    I: IntoIterator<Item = Interval>,
+{
+    // Every interval adds one where it starts and takes it back just past its end, which for
+    // an interval ending at `u64::MAX` only exists as a `u128`.
+    let mut events = intervals
+        .into_iter()
+        .flat_map(|interval| {
+            [
+                (u128::from(interval.start), 1),
+                (u128::from(interval.end) + 1, -1),
+            ]
+        })
+        .collect::<Vec<(u128, i64)>>();
+    events.sort_unstable();
+    let mut covered = vec![];
+    let mut depth = 0;
+    let mut open = None;
+    for (i, &(at, change)) in events.iter().enumerate() {
+        depth += change;
+        if events.get(i + 1).is_some_and(|&(next, _)| next == at) {
+            continue;
+        }
+        match (open, depth > k as i64) {
+            (None, true) => open = Some(at as u64),
+            (Some(start), false) => {
+                covered.push(Interval {
+                    start,
+                    end: (at - 1) as u64,
+                });
+                open = None;
+            }
+            _ => {}
+        }
+    }
+    covered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    fn interval(start: u64, end: u64) -> Interval {
+        Interval::new(start, end).unwrap()
+    }
+
+    #[test]
+    fn intervals_combine() {
+        let (a, b) = (interval(2, 6), interval(4, 8));
+        assert!(a.overlaps(&b) && !a.contains(&b));
+        assert!(a.contains(&interval(3, 6)));
+        assert_eq!(a.intersection(&b), Some(interval(4, 6)));
+        assert_eq!(a.intersection(&interval(7, 9)), None);
+        assert_eq!(a.union(&interval(7, 9)), Some(interval(2, 9)));
+        assert_eq!(a.union(&interval(8, 9)), None);
+        assert_eq!(Interval::new(5, 4), None);
+        assert_eq!(interval(0, u64::MAX).length(), u64::MAX);
+
+        let elves = [
+            interval(5, 7),
+            interval(1, 3),
+            interval(2, 4),
+            interval(9, 9),
+        ];
+        assert_eq!(merge(elves), [interval(1, 7), interval(9, 9)]);
+        assert_eq!(gaps(elves), [interval(8, 8)]);
+        assert_eq!(covered_length(elves), 8);
+        assert_eq!(covered_more_than(elves, 1), [interval(2, 3)]);
+        assert_eq!(covered_more_than(elves, 2), []);
+        assert_eq!(
+            covered_more_than([interval(u64::MAX, u64::MAX)], 0),
+            [interval(u64::MAX, u64::MAX)]
+        );
+    }
+
+    /// Counts the elves on every section one by one.
+    fn reference(intervals: &[Interval], k: usize) -> Vec<u64> {
+        (0..=70)
+            .filter(|&section| {
+                intervals
+                    .iter()
+                    .filter(|interval| interval.contains_section(section))
+                    .count()
+                    > k
+            })
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn matches_reference(ends in vec((0..50u64, 0..20u64), 0..12), k in 0..4usize) {
+            let intervals = ends
+                .iter()
+                .map(|&(start, length)| interval(start, start + length))
+                .collect::<Vec<_>>();
+            let sections = |intervals: Vec<Interval>| {
+                intervals
+                    .iter()
+                    .flat_map(|interval| interval.start..=interval.end)
+                    .collect::<Vec<_>>()
+            };
+            prop_assert_eq!(sections(covered_more_than(intervals.clone(), k)), reference(&intervals, k));
+            let merged = merge(intervals.clone());
+            prop_assert_eq!(&merged, &covered_more_than(intervals.clone(), 0));
+            prop_assert_eq!(covered_length(intervals.clone()), reference(&intervals, 0).len() as u64);
+            let uncovered = sections(gaps(intervals.clone()));
+            prop_assert!(uncovered.iter().all(|&section| !reference(&intervals, 0).contains(&section)));
+            prop_assert_eq!(uncovered.len() as u64 + covered_length(intervals), merged.last().map_or(0, |last| last.end + 1 - merged[0].start));
+        }
+    }
+}
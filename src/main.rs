@@ -1,5 +1,8 @@
 #![allow(unused)]
 
+mod answers;
+mod bench;
+mod cli;
 mod day1;
 mod day2;
 mod day3;
@@ -7,15 +10,26 @@ mod day4;
 mod day5;
 mod day6;
 mod day_template;
+mod diagnostics;
+mod error;
+mod examples;
+mod execution;
+mod generate;
+mod inputs;
+mod interval;
 mod parsers;
+mod registry;
+mod scaffold;
+mod solution;
+mod streaming;
+mod submit;
 mod utils;
 
 extern crate rayon;
 
 fn main() {
-    use day6::{task_a, task_b};
-
-    let day_file_path = "data/day6.txt";
-    println!("{}", task_a(day_file_path).unwrap());
-    println!("{}", task_b(day_file_path).unwrap());
+    if let Err(error) = cli::run(std::env::args().skip(1)) {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    }
 }
@@ -1,9 +1,233 @@
-use nom::character::complete::digit1;
-use nom::combinator::map_res;
+use nom::bytes::complete::{tag, take_till1};
+use nom::character::complete::{char, digit1, line_ending, none_of, one_of, space0, space1};
+use nom::combinator::{map_res, opt, recognize};
+use nom::error::{context, ContextError, ErrorKind, FromExternalError, ParseError};
+use nom::multi::{many1, separated_list1};
+use nom::sequence::{delimited, pair, preceded, separated_pair, terminated, tuple};
+use nom::IResult;
 use std::str::FromStr;
 
-pub fn integer(input: &str) -> nom::IResult<&str, u64> {
-    map_res(digit1, u64::from_str)(input)
+use crate::error::{AocError, AocResult};
+
+/// Where a parser gave up and what it was looking for there.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SyntaxError<'a> {
+    pub input: &'a str,
+    pub expected: String,
+}
+
+pub type ParseResult<'a, O> = IResult<&'a str, O, SyntaxError<'a>>;
+
+fn describe(kind: ErrorKind) -> String {
+    match kind {
+        ErrorKind::Digit => "digit".to_string(),
+        ErrorKind::Alpha => "letter".to_string(),
+        ErrorKind::Space | ErrorKind::MultiSpace => "whitespace".to_string(),
+        ErrorKind::CrLf => "line ending".to_string(),
+        ErrorKind::Eof => "end of input".to_string(),
+        ErrorKind::TakeTill1 => "key".to_string(),
+        kind => format!("{:?}", kind).to_lowercase(),
+    }
+}
+
+impl<'a> ParseError<&'a str> for SyntaxError<'a> {
+    fn from_error_kind(input: &'a str, kind: ErrorKind) -> Self {
+        SyntaxError {
+            input,
+            expected: describe(kind),
+        }
+    }
+
+    fn append(_: &'a str, _: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    fn from_char(input: &'a str, c: char) -> Self {
+        SyntaxError {
+            input,
+            expected: format!("{:?}", c),
+        }
+    }
+
+    /// Keeps the alternative that got further, or lists both when they failed at the same spot.
+    fn or(self, other: Self) -> Self {
+        match self.input.len().cmp(&other.input.len()) {
+            std::cmp::Ordering::Less => self,
+            std::cmp::Ordering::Greater => other,
+            std::cmp::Ordering::Equal => SyntaxError {
+                input: self.input,
+                expected: format!("{} or {}", self.expected, other.expected),
+            },
+        }
+    }
+}
+
+impl<'a> ContextError<&'a str> for SyntaxError<'a> {
+    fn add_context(input: &'a str, context: &'static str, mut other: Self) -> Self {
+        if other.input.len() == input.len() {
+            other.expected = context.to_string();
+        }
+        other
+    }
+}
+
+impl<'a, E> FromExternalError<&'a str, E> for SyntaxError<'a> {
+    fn from_external_error(input: &'a str, kind: ErrorKind, _: E) -> Self {
+        SyntaxError::from_error_kind(input, kind)
+    }
+}
+
+fn cut_error(error: nom::Err<SyntaxError>) -> nom::Err<SyntaxError> {
+    match error {
+        nom::Err::Error(error) => nom::Err::Failure(error),
+        error => error,
+    }
+}
+
+/// A literal token, reported as expected by its text when missing.
+pub fn token<'a>(token: &'a str) -> impl FnMut(&'a str) -> ParseResult<'a, &'a str> {
+    move |input| {
+        tag(token)(input).map_err(|error: nom::Err<SyntaxError>| {
+            error.map(|error| SyntaxError {
+                expected: format!("{:?}", token),
+                ..error
+            })
+        })
+    }
+}
+
+pub fn integer(input: &str) -> ParseResult<'_, u64> {
+    unsigned(input)
+}
+
+/// Any unsigned integer type, written as plain digits.
+pub fn unsigned<T>(input: &str) -> ParseResult<'_, T>
+where
+    T: FromStr,
+{
+    context("number", map_res(digit1, T::from_str))(input)
+}
+
+/// Any integer type, with an optional leading `+` or `-`.
+pub fn signed<T>(input: &str) -> ParseResult<'_, T>
+where
+    T: FromStr,
+{
+    context(
+        "signed number",
+        map_res(recognize(pair(opt(one_of("+-")), digit1)), T::from_str),
+    )(input)
+}
+
+/// One `item` per line, stopping at a blank line or the end of input. Once a line has
+/// started, it must parse as `item`, so the error points inside the broken line.
+pub fn lines<'a, O, F>(mut item: F) -> impl FnMut(&'a str) -> ParseResult<'a, Vec<O>>
+where
+    F: FnMut(&'a str) -> ParseResult<'a, O>,
+{
+    move |input| {
+        let (mut input, first) = item(input)?;
+        let mut items = vec![first];
+        while let Ok((rest, _)) = line_ending::<_, SyntaxError>(input) {
+            if rest
+                .lines()
+                .next()
+                .is_none_or(|line| line.trim().is_empty())
+            {
+                break;
+            }
+            let (rest, next) = item(rest).map_err(cut_error)?;
+            items.push(next);
+            input = rest;
+        }
+        Ok((input, items))
+    }
+}
+
+/// Blocks separated by one or more blank lines, each parsed by `block`.
+pub fn blocks<'a, O, F>(mut block: F) -> impl FnMut(&'a str) -> ParseResult<'a, Vec<O>>
+where
+    F: FnMut(&'a str) -> ParseResult<'a, O>,
+{
+    move |input| {
+        let (mut input, first) = block(input)?;
+        let mut blocks = vec![first];
+        let mut separator = pair(
+            line_ending::<_, SyntaxError>,
+            many1(preceded(space0, line_ending)),
+        );
+        while let Ok((rest, _)) = separator(input) {
+            if rest.trim_start().is_empty() {
+                break;
+            }
+            let (rest, next) = block(rest).map_err(cut_error)?;
+            blocks.push(next);
+            input = rest;
+        }
+        Ok((input, blocks))
+    }
+}
+
+/// A rectangle-ish block of lines, every character mapped through `cell`.
+pub fn grid_of<'a, O, F>(cell: F) -> impl FnMut(&'a str) -> ParseResult<'a, Vec<Vec<O>>>
+where
+    F: FnMut(&'a str) -> ParseResult<'a, O>,
+{
+    lines(many1(cell))
+}
+
+pub fn grid(input: &str) -> ParseResult<'_, Vec<Vec<char>>> {
+    grid_of(none_of("\r\n"))(input)
+}
+
+/// Items separated by commas, with optional spaces around them.
+pub fn comma_list<'a, O, F>(item: F) -> impl FnMut(&'a str) -> ParseResult<'a, Vec<O>>
+where
+    F: FnMut(&'a str) -> ParseResult<'a, O>,
+{
+    separated_list1(tuple((space0, char(','), space0)), item)
+}
+
+/// Items separated by spaces or tabs, ignoring the padding around the whole list.
+pub fn whitespace_list<'a, O, F>(item: F) -> impl FnMut(&'a str) -> ParseResult<'a, Vec<O>>
+where
+    F: FnMut(&'a str) -> ParseResult<'a, O>,
+{
+    delimited(space0, separated_list1(space1, item), space0)
+}
+
+/// A `label: value` line with a known label.
+pub fn labelled<'a, O, F>(label: &'a str, value: F) -> impl FnMut(&'a str) -> ParseResult<'a, O>
+where
+    F: FnMut(&'a str) -> ParseResult<'a, O>,
+{
+    preceded(tuple((token(label), char(':'), space0)), value)
+}
+
+/// A `key: value` line, returning the key as written.
+pub fn key_value<'a, O, F>(value: F) -> impl FnMut(&'a str) -> ParseResult<'a, (&'a str, O)>
+where
+    F: FnMut(&'a str) -> ParseResult<'a, O>,
+{
+    separated_pair(
+        take_till1(|c| c == ':' || c == '\n'),
+        terminated(char(':'), space0),
+        value,
+    )
+}
+
+/// Runs `parser` over the whole `input`, allowing only trailing whitespace. Errors carry the
+/// line and column they happened at, leftovers are reported as `expected`.
+pub fn parse_complete<'a, O, F>(input: &'a str, mut parser: F, expected: &str) -> AocResult<O>
+where
+    F: FnMut(&'a str) -> ParseResult<'a, O>,
+{
+    let (rest, output) = parser(input).map_err(|e| AocError::from_nom(input, e))?;
+    let rest = rest.trim_start();
+    if !rest.is_empty() {
+        return Err(AocError::parse_at(input, rest, expected));
+    }
+    Ok(output)
 }
 
 #[cfg(test)]
@@ -15,4 +239,46 @@ mod tests {
         let result = integer("64").unwrap().1;
         assert_eq!(result, 64)
     }
+
+    #[test]
+    fn signed_parses() {
+        assert_eq!(signed::<i32>("-17,").unwrap(), (",", -17));
+        assert_eq!(signed::<i8>("+5").unwrap().1, 5);
+        assert!(signed::<u8>("-5").is_err());
+    }
+
+    #[test]
+    fn blocks_and_lists_parse() {
+        let input = "1, 2,3\n4\n\n\n5\n";
+        let result = parse_complete(
+            input,
+            blocks(lines(comma_list(integer))),
+            "expected numbers",
+        );
+        assert_eq!(
+            result.unwrap(),
+            vec![vec![vec![1, 2, 3], vec![4]], vec![vec![5]]]
+        );
+    }
+
+    #[test]
+    fn grid_and_labels_parse() {
+        assert_eq!(
+            grid("#.\n.#\n").unwrap().1,
+            vec![vec!['#', '.'], vec!['.', '#']]
+        );
+        let items = labelled("Starting items", comma_list(integer))("Starting items: 79, 98");
+        assert_eq!(items.unwrap().1, vec![79, 98]);
+        let (key, value) = key_value(whitespace_list(integer))("Test: 1 2 ").unwrap().1;
+        assert_eq!((key, value), ("Test", vec![1, 2]));
+    }
+
+    #[test]
+    fn leftovers_are_reported_with_position() {
+        let error = parse_complete("1\n2\nx\n", lines(integer), "expected number").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Parse error at line 3, column 1: expected number\n3 | x\n  | ^"
+        );
+    }
 }
@@ -0,0 +1,213 @@
+use std::io::{self, BufRead};
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::bench::{self, Timings};
+use crate::error::AocResult;
+use crate::generate;
+use crate::solution::{self, Answer, Part};
+use crate::streaming;
+use crate::utils;
+use crate::{day1, day2, day3, day4, day5, day6};
+
+type Solver = fn(&mut dyn BufRead, &[Part]) -> AocResult<Vec<Answer>>;
+type TextSolver = fn(&str, &[Part]) -> AocResult<Vec<Answer>>;
+type Timer = fn(&str, usize) -> AocResult<Timings>;
+type Generator = fn(u64, usize) -> String;
+
+#[derive(Error, Debug)]
+pub enum RegistryError {
+    #[error("Day {0} is not implemented")]
+    NotImplemented(u8),
+
+    #[error("Day {0} has no input generator")]
+    NoGenerator(u8),
+}
+
+/// Optional capabilities of a day, see the `day!` macro.
+struct Features {
+    streamer: Option<Solver>,
+    generator: Option<Generator>,
+}
+
+impl Features {
+    const NONE: Features = Features {
+        streamer: None,
+        generator: None,
+    };
+}
+
+pub struct Day {
+    pub day: u8,
+    solver: Solver,
+    text_solver: TextSolver,
+    timer: Timer,
+    features: Features,
+}
+
+impl Day {
+    pub fn run(&self, parts: &[Part], input: &Path) -> AocResult<Vec<Answer>> {
+        self.run_reader(parts, &mut utils::get_input_file(input)?)
+    }
+
+    pub fn run_reader(&self, parts: &[Part], input: &mut dyn BufRead) -> AocResult<Vec<Answer>> {
+        (self.solver)(input, parts)
+    }
+
+    /// Streams the file through days that support it. The others get it memory mapped and parse
+    /// it from the map with `Solution::parse_str`, so only what the solution keeps is copied.
+    pub fn stream(&self, parts: &[Part], input: &Path) -> AocResult<Vec<Answer>> {
+        match self.features.streamer {
+            Some(streamer) => streamer(&mut utils::get_input_file(input)?, parts),
+            None => {
+                let map = utils::map_input_file(input)?;
+                let text = std::str::from_utf8(&map)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                (self.text_solver)(text, parts)
+            }
+        }
+    }
+
+    pub fn stream_reader(&self, parts: &[Part], input: &mut dyn BufRead) -> AocResult<Vec<Answer>> {
+        (self.features.streamer.unwrap_or(self.solver))(input, parts)
+    }
+
+    /// A random input for `seed`, roughly `size` records long.
+    pub fn generate(&self, seed: u64, size: usize) -> Result<String, RegistryError> {
+        let generator = self
+            .features
+            .generator
+            .ok_or(RegistryError::NoGenerator(self.day))?;
+        Ok(generator(seed, size))
+    }
+
+    pub fn time(&self, input: &str, runs: usize) -> AocResult<Timings> {
+        (self.timer)(input, runs)
+    }
+}
+
+macro_rules! feature {
+    (streamer, $solution:ty) => {
+        streaming::solve::<$solution>
+    };
+    (generator, $solution:ty) => {
+        generate::seeded::<$solution>
+    };
+}
+
+/// `day!(N, dayN::DayN, streamer, generator)` registers a solution, followed by the optional
+/// features it implements.
+macro_rules! day {
+    ($day:expr, $solution:ty $(, $feature:ident)*) => {
+        Day {
+            day: $day,
+            solver: solution::solve::<$solution>,
+            text_solver: solution::solve_str::<$solution>,
+            timer: bench::measure::<$solution>,
+            features: Features {
+                $($feature: Some(feature!($feature, $solution)),)*
+                ..Features::NONE
+            },
+        }
+    };
+}
+
+// Days with every feature fill `Features` completely.
+#[allow(clippy::needless_update)]
+static DAYS: &[Day] = &[
+    day!(1, day1::Day1, streamer, generator),
+    day!(2, day2::Day2, streamer, generator),
+    day!(3, day3::Day3, streamer, generator),
+    day!(4, day4::Day4, streamer, generator),
+    day!(5, day5::Day5, generator),
+    day!(6, day6::Day6, streamer, generator),
+];
+
+pub fn all_days() -> &'static [Day] {
+    DAYS
+}
+
+pub fn get_day(day: u8) -> Result<&'static Day, RegistryError> {
+    DAYS.iter()
+        .find(|entry| entry.day == day)
+        .ok_or(RegistryError::NotImplemented(day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::Execution;
+
+    #[test]
+    fn get_day_works() {
+        let result = get_day(4)
+            .unwrap()
+            .run(&[Part::A, Part::B], Path::new("data/day4t.txt"));
+        assert_eq!(result.unwrap(), vec![Answer::Number(2), Answer::Number(4)]);
+        assert!(matches!(
+            get_day(25),
+            Err(RegistryError::NotImplemented(25))
+        ));
+    }
+
+    #[test]
+    fn run_reader_works() {
+        let mut input = "A Y\nB X\nC Z\n".as_bytes();
+        let result = get_day(2).unwrap().run_reader(&[Part::B], &mut input);
+        assert_eq!(result.unwrap(), vec![Answer::Number(12)]);
+    }
+
+    #[test]
+    fn days_are_ordered() {
+        let days = all_days().iter().map(|day| day.day).collect::<Vec<_>>();
+        assert!(days.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(days[..6], [1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn executions_agree() {
+        for day in all_days().iter().filter(|day| day.day <= 6) {
+            let input = Path::new("data").join(format!("day{}t.txt", day.day));
+            let parts = [Part::A, Part::B];
+            let sequential = Execution::Sequential.install(|| day.run(&parts, &input));
+            let threaded = Execution::Threads(4).install(|| day.run(&parts, &input));
+            assert_eq!(sequential.unwrap(), threaded.unwrap(), "day {}", day.day);
+        }
+    }
+
+    #[test]
+    fn streaming_agrees() {
+        for day in all_days().iter().filter(|day| day.day <= 6) {
+            let input = Path::new("data").join(format!("day{}t.txt", day.day));
+            let parts = [Part::A, Part::B];
+            let streamed = day.stream(&parts, &input).unwrap();
+            assert_eq!(
+                streamed,
+                day.run(&parts, &input).unwrap(),
+                "day {}",
+                day.day
+            );
+        }
+        let day = get_day(4).unwrap();
+        for input in [
+            "2-4,6-8\n\n2-3,4-5\n",
+            "2-4;6-8\n",
+            "2-4,6-x\n",
+            "2-4,6-8\n   \n",
+            "2-4,6-8  \n2-3,4-5\n",
+            "",
+        ] {
+            let streamed = day.stream_reader(&[Part::A], &mut input.as_bytes());
+            let parsed = day.run_reader(&[Part::A], &mut input.as_bytes());
+            match (streamed, parsed) {
+                (Ok(streamed), Ok(parsed)) => assert_eq!(streamed, parsed, "{input:?}"),
+                (streamed, parsed) => assert_eq!(
+                    streamed.unwrap_err().to_string(),
+                    parsed.unwrap_err().to_string(),
+                    "{input:?}"
+                ),
+            }
+        }
+    }
+}
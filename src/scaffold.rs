@@ -0,0 +1,196 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crate::error::{AocError, AocResult};
+
+const TEMPLATE_DAY: &str = "pub const DAY: u8 = 0;";
+
+/// Generates new days inside the crate rooted at `root`.
+pub struct Scaffold {
+    root: PathBuf,
+}
+
+fn leading_number(text: &str) -> Option<u8> {
+    let digits = text
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>();
+    digits.parse().ok()
+}
+
+/// Inserts `new_line` among the lines starting with `prefix` followed by a day number, keeping
+/// them ordered by that number.
+fn insert_ordered(text: &str, prefix: &str, day: u8, new_line: &str) -> AocResult<String> {
+    let mut lines = text.lines().collect::<Vec<_>>();
+    let numbered = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let rest = line.trim_start().strip_prefix(prefix)?;
+            leading_number(rest).map(|number| (i, number))
+        })
+        .collect::<Vec<_>>();
+    if numbered.iter().any(|(_, number)| *number == day) {
+        return Err(AocError::DayExists(day));
+    }
+    let position = numbered
+        .iter()
+        .find(|(_, number)| *number > day)
+        .map(|(i, _)| *i)
+        .or_else(|| numbered.last().map(|(i, _)| i + 1))
+        .ok_or(AocError::MissingSection("day list"))?;
+    lines.insert(position, new_line);
+    Ok(lines.join("\n") + "\n")
+}
+
+fn render_template(template: &str, day: u8) -> AocResult<String> {
+    if !template.contains(TEMPLATE_DAY) {
+        return Err(AocError::MissingSection("template DAY constant"));
+    }
+    let body = template
+        .lines()
+        .skip_while(|line| line.starts_with("//!") || line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok(body
+        .replace(TEMPLATE_DAY, &format!("pub const DAY: u8 = {};", day))
+        .replace("DayN", &format!("Day{}", day))
+        + "\n")
+}
+
+fn add_module_import(registry: &str, day: u8) -> AocResult<String> {
+    let import = registry
+        .lines()
+        .find(|line| line.starts_with("use crate::{day"))
+        .ok_or(AocError::MissingSection("registry imports"))?;
+    let mut modules = import
+        .trim_start_matches("use crate::{")
+        .trim_end_matches("};")
+        .split(", ")
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    modules.push(format!("day{}", day));
+    modules.sort_by_key(|module| leading_number(&module[3..]));
+    Ok(registry.replacen(
+        import,
+        &format!("use crate::{{{}}};", modules.join(", ")),
+        1,
+    ))
+}
+
+impl Scaffold {
+    pub fn new<P>(root: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Scaffold { root: root.into() }
+    }
+
+    fn src(&self, file: &str) -> PathBuf {
+        self.root.join("src").join(file)
+    }
+
+    /// Creates `src/dayN.rs`, declares and registers it, and adds empty input and example
+    /// fixtures unless they already exist. Returns the created files.
+    pub fn create_day(&self, day: u8) -> AocResult<Vec<PathBuf>> {
+        let day_path = self.src(&format!("day{}.rs", day));
+        if day_path.exists() {
+            return Err(AocError::DayExists(day));
+        }
+        let day_source = render_template(&fs::read_to_string(self.src("day_template.rs"))?, day)?;
+        let main = insert_ordered(
+            &fs::read_to_string(self.src("main.rs"))?,
+            "mod day",
+            day,
+            &format!("mod day{};", day),
+        )?;
+        let registry = insert_ordered(
+            &fs::read_to_string(self.src("registry.rs"))?,
+            "day!(",
+            day,
+            &format!("    day!({}, day{}::Day{}),", day, day, day),
+        )?;
+        let registry = add_module_import(&registry, day)?;
+
+        fs::write(&day_path, day_source)?;
+        fs::write(self.src("main.rs"), main)?;
+        fs::write(self.src("registry.rs"), registry)?;
+        let mut created = vec![day_path];
+        let data = self.root.join("data");
+        fs::create_dir_all(&data)?;
+        // Only the example fixture, `dayN.txt` is left for the input cache to download. An empty
+        // `dayN.txt` would count as cached and the real input would never be fetched.
+        let fixture = data.join(format!("day{}t.txt", day));
+        if !fixture.exists() {
+            fs::write(&fixture, "")?;
+            created.push(fixture);
+        }
+        Ok(created)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inputs::tests::temp_dir;
+    use crate::inputs::{InputCache, UreqClient};
+
+    const MAIN: &str = "mod day1;\nmod day4;\nmod day6;\nmod day_template;\n";
+    const REGISTRY: &str = "use crate::{day1, day4, day6};
+
+static DAYS: &[Day] = &[
+    day!(1, day1::Day1),
+    day!(4, day4::Day4),
+    day!(6, day6::Day6),
+];
+";
+
+    fn crate_copy(name: &str) -> PathBuf {
+        let root = temp_dir(name);
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/main.rs"), MAIN).unwrap();
+        fs::write(root.join("src/registry.rs"), REGISTRY).unwrap();
+        fs::copy("src/day_template.rs", root.join("src/day_template.rs")).unwrap();
+        root
+    }
+
+    #[test]
+    fn create_day_wires_everything() {
+        let root = crate_copy("scaffold");
+        let created = Scaffold::new(&root).create_day(5).unwrap();
+        assert_eq!(created.len(), 2);
+        let source = fs::read_to_string(root.join("src/day5.rs")).unwrap();
+        assert!(source.contains("pub struct Day5 {"));
+        assert!(source.contains("pub const DAY: u8 = 5;"));
+        assert!(!source.contains("//!"));
+        let main = fs::read_to_string(root.join("src/main.rs")).unwrap();
+        assert!(main.contains("mod day4;\nmod day5;\nmod day6;"));
+        let registry = fs::read_to_string(root.join("src/registry.rs")).unwrap();
+        assert!(registry.contains("use crate::{day1, day4, day5, day6};"));
+        assert!(registry.contains("    day!(5, day5::Day5),\n    day!(6, day6::Day6),\n"));
+    }
+
+    #[test]
+    fn new_day_input_is_left_to_the_cache() {
+        let root = crate_copy("scaffold-input");
+        Scaffold::new(&root).create_day(5).unwrap();
+        assert!(root.join("data/day5t.txt").exists());
+        let cache = InputCache::new(root.join("data"), UreqClient::default());
+        assert!(matches!(cache.resolve(5), Err(AocError::MissingSession(5))));
+    }
+
+    #[test]
+    fn create_day_refuses_existing_day() {
+        let root = crate_copy("scaffold-existing");
+        assert!(matches!(
+            Scaffold::new(&root).create_day(4),
+            Err(AocError::DayExists(4))
+        ));
+        let scaffold = Scaffold::new(&root);
+        scaffold.create_day(12).unwrap();
+        assert!(matches!(
+            scaffold.create_day(12),
+            Err(AocError::DayExists(12))
+        ));
+    }
+}
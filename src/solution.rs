@@ -0,0 +1,172 @@
+use std::convert::Infallible;
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+use std::path::Path;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::error::AocResult;
+use crate::utils;
+
+#[derive(Error, Debug)]
+#[error("Unknown part {0}, expected \"a\" or \"b\"")]
+pub struct UnknownPart(String);
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Part {
+    A,
+    B,
+}
+
+impl FromStr for Part {
+    type Err = UnknownPart;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "a" | "A" | "1" => Ok(Part::A),
+            "b" | "B" | "2" => Ok(Part::B),
+            _ => Err(UnknownPart(s.to_string())),
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::A => write!(f, "a"),
+            Part::B => write!(f, "b"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Answer {
+    Number(u64),
+    Text(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Number(number) => write!(f, "{}", number),
+            Answer::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+impl Answer {
+    /// Reads back a displayed answer, numbers become `Answer::Number`. Never fails.
+    pub fn parse(text: &str) -> Answer {
+        match u64::from_str(text) {
+            Ok(number) => Answer::Number(number),
+            Err(_) => Answer::Text(text.to_string()),
+        }
+    }
+}
+
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Answer::parse(s))
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(number: u64) -> Self {
+        Answer::Number(number)
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(number: u32) -> Self {
+        Answer::Number(number as u64)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(number: usize) -> Self {
+        Answer::Number(number as u64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::Text(text.to_string())
+    }
+}
+
+/// A single day of the calendar: the input is parsed once and both parts are answered from it.
+pub trait Solution: Sized {
+    fn parse<R>(input: R) -> AocResult<Self>
+    where
+        R: BufRead;
+
+    /// Days that need the whole text anyway override this to parse it in place, which lets
+    /// memory mapped input be solved without copying it.
+    fn parse_str(input: &str) -> AocResult<Self> {
+        Self::parse(input.as_bytes())
+    }
+
+    fn parse_file<P>(file: P) -> AocResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Self::parse(utils::get_input_file(file)?)
+    }
+
+    fn part_one(&self) -> AocResult<Answer>;
+
+    fn part_two(&self) -> AocResult<Answer>;
+
+    fn part(&self, part: Part) -> AocResult<Answer> {
+        match part {
+            Part::A => self.part_one(),
+            Part::B => self.part_two(),
+        }
+    }
+}
+
+/// Parses `input` once and answers every requested part in order.
+pub fn solve<S>(input: &mut dyn BufRead, parts: &[Part]) -> AocResult<Vec<Answer>>
+where
+    S: Solution,
+{
+    let solution = S::parse(input)?;
+    parts.iter().map(|part| solution.part(*part)).collect()
+}
+
+pub fn solve_str<S>(input: &str, parts: &[Part]) -> AocResult<Vec<Answer>>
+where
+    S: Solution,
+{
+    let solution = S::parse_str(input)?;
+    parts.iter().map(|part| solution.part(*part)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answer_displays() {
+        assert_eq!(Answer::from(24000u64).to_string(), "24000");
+        assert_eq!(Answer::from("CMZ").to_string(), "CMZ");
+        assert_eq!(Answer::from(15u32), Answer::Number(15));
+        assert_eq!(Answer::parse("157"), Answer::Number(157));
+        assert_eq!(Answer::parse("MCD"), Answer::from("MCD"));
+    }
+
+    #[test]
+    fn part_parses() {
+        assert_eq!(Part::from_str("b").unwrap(), Part::B);
+        assert!(Part::from_str("c").is_err());
+    }
+}
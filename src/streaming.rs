@@ -0,0 +1,234 @@
+use std::io::BufRead;
+
+use crate::error::{AocError, AocResult};
+use crate::parsers::ParseResult;
+use crate::solution::{Answer, Part};
+
+/// Days that answer both parts in one pass, keeping only a bounded part of the input around.
+pub trait Streaming {
+    fn stream<R>(input: R) -> AocResult<[Answer; 2]>
+    where
+        R: BufRead;
+}
+
+pub fn solve<S>(input: &mut dyn BufRead, parts: &[Part]) -> AocResult<Vec<Answer>>
+where
+    S: Streaming,
+{
+    let [part_one, part_two] = S::stream(input)?;
+    Ok(parts
+        .iter()
+        .map(|part| match part {
+            Part::A => part_one.clone(),
+            Part::B => part_two.clone(),
+        })
+        .collect())
+}
+
+/// Calls `f` with every line and its 1-based number, reusing a single buffer.
+pub fn for_each_line<R, F>(mut input: R, mut f: F) -> AocResult<()>
+where
+    R: BufRead,
+    F: FnMut(usize, &str) -> AocResult<()>,
+{
+    let mut buffer = String::new();
+    let mut line = 0;
+    loop {
+        buffer.clear();
+        if input.read_line(&mut buffer)? == 0 {
+            return Ok(());
+        }
+        line += 1;
+        let text = buffer.strip_suffix('\n').unwrap_or(&buffer);
+        f(line, text.strip_suffix('\r').unwrap_or(text))?;
+    }
+}
+
+/// What `for_each_group` reports, in input order.
+pub enum Group<'a> {
+    /// A non-blank line and its 1-based number.
+    Line(usize, &'a str),
+    /// The group that just ended had at least one line.
+    End,
+}
+
+/// Walks groups of lines separated by blank lines. Runs of blank lines count as one separator
+/// and leading or trailing blank lines never produce empty groups.
+pub fn for_each_group<R, F>(input: R, mut f: F) -> AocResult<()>
+where
+    R: BufRead,
+    F: FnMut(Group<'_>) -> AocResult<()>,
+{
+    let mut in_group = false;
+    for_each_line(input, |line, text| {
+        if !text.trim().is_empty() {
+            in_group = true;
+            f(Group::Line(line, text))
+        } else if in_group {
+            in_group = false;
+            f(Group::End)
+        } else {
+            Ok(())
+        }
+    })?;
+    if in_group {
+        f(Group::End)?;
+    }
+    Ok(())
+}
+
+/// Collects blank-line separated groups, turning every line into a value with `value`.
+pub fn read_groups<R, T, F>(input: R, mut value: F) -> AocResult<Vec<Vec<T>>>
+where
+    R: BufRead,
+    F: FnMut(usize, &str) -> AocResult<T>,
+{
+    let mut groups = vec![];
+    let mut current = vec![];
+    for_each_group(input, |event| {
+        match event {
+            Group::Line(line, text) => current.push(value(line, text)?),
+            Group::End => groups.push(std::mem::take(&mut current)),
+        }
+        Ok(())
+    })?;
+    Ok(groups)
+}
+
+fn shift_lines(mut error: AocError, lines: usize) -> AocError {
+    if let AocError::Parse { line, .. } = &mut error {
+        *line += lines;
+    }
+    error
+}
+
+/// Parses every line of `input` as one record with `parser` and calls `f` with it.
+///
+/// Errors agree with `parse_complete(input, lines(parser), expected)`: once a blank line or text
+/// behind a record has been seen, anything but whitespace is reported with the `expected`
+/// message, so blank lines are only fine at the end.
+pub fn for_each_record<R, O, P, F>(
+    mut input: R,
+    mut parser: P,
+    expected: &str,
+    mut f: F,
+) -> AocResult<()>
+where
+    R: BufRead,
+    P: FnMut(&str) -> ParseResult<'_, O>,
+    F: FnMut(O) -> AocResult<()>,
+{
+    if input.fill_buf()?.is_empty() {
+        let (_, record) = parser("").map_err(|error| AocError::from_nom("", error))?;
+        return f(record);
+    }
+    let mut started = false;
+    let mut ended = false;
+    for_each_line(input, |line, text| {
+        let rest = if ended || (started && text.trim().is_empty()) {
+            ended = true;
+            text
+        } else {
+            started = true;
+            let (rest, record) = parser(text)
+                .map_err(|error| shift_lines(AocError::from_nom(text, error), line - 1))?;
+            f(record)?;
+            ended = !rest.is_empty();
+            rest
+        };
+        let rest = rest.trim_start();
+        if rest.is_empty() {
+            Ok(())
+        } else {
+            let error = AocError::parse_at(text, rest, expected);
+            Err(shift_lines(error, line - 1))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::integer as number;
+    use std::io::BufReader;
+
+    #[test]
+    fn records_survive_chunk_boundaries() {
+        let input = BufReader::with_capacity(3, "12345\n67\r\n8".as_bytes());
+        let mut numbers = vec![];
+        for_each_record(input, number, "expected number", |n| {
+            numbers.push(n);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(numbers, vec![12345, 67, 8]);
+    }
+
+    #[test]
+    fn record_errors_keep_their_line() {
+        let input = BufReader::with_capacity(4, "1\n2\n3\n4x\n".as_bytes());
+        let error = for_each_record(input, number, "expected number", |_| Ok(())).unwrap_err();
+        assert!(matches!(
+            error,
+            AocError::Parse {
+                line: 4,
+                column: 2,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn only_trailing_blank_lines_are_allowed() {
+        let input = BufReader::with_capacity(2, "1\n2 \n  \n\n".as_bytes());
+        for_each_record(input, number, "expected number", |_| Ok(())).unwrap();
+        let input = BufReader::with_capacity(2, "1\n\r\n\n3\n".as_bytes());
+        let error = for_each_record(input, number, "expected number", |_| Ok(())).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Parse error at line 4, column 1: expected number\n4 | 3\n  | ^"
+        );
+        let input = BufReader::with_capacity(2, "1\n2 3\n".as_bytes());
+        let error = for_each_record(input, number, "expected number", |_| Ok(())).unwrap_err();
+        assert!(matches!(
+            error,
+            AocError::Parse {
+                line: 2,
+                column: 3,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn groups_ignore_extra_blank_lines() {
+        let input = "\r\n1\r\n2\r\n\r\n  \r\n3\n\n\n";
+        let groups = read_groups(input.as_bytes(), |line, text| Ok((line, text.to_string())));
+        assert_eq!(
+            groups.unwrap(),
+            vec![
+                vec![(2, "1".to_string()), (3, "2".to_string())],
+                vec![(6, "3".to_string())]
+            ]
+        );
+    }
+
+    #[test]
+    fn lines_are_numbered_without_endings() {
+        let mut lines = vec![];
+        for_each_line("a\r\nb\n\nc".as_bytes(), |line, text| {
+            lines.push((line, text.to_string()));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(
+            lines,
+            vec![
+                (1, "a".to_string()),
+                (2, "b".to_string()),
+                (3, String::new()),
+                (4, "c".to_string())
+            ]
+        );
+    }
+}
@@ -0,0 +1,321 @@
+use std::fmt::{Display, Formatter};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::error::{AocError, AocResult};
+use crate::inputs::{HttpClient, UreqClient, DEFAULT_BASE_URL};
+use crate::solution::{Answer, Part};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    RateLimited,
+    WrongLevel,
+}
+
+impl Verdict {
+    /// Reads the verdict out of the page returned after posting an answer.
+    pub fn from_response(body: &str) -> Option<Verdict> {
+        if body.contains("That's the right answer") {
+            Some(Verdict::Correct)
+        } else if body.contains("That's not the right answer") {
+            if body.contains("too high") {
+                Some(Verdict::TooHigh)
+            } else if body.contains("too low") {
+                Some(Verdict::TooLow)
+            } else {
+                Some(Verdict::Wrong)
+            }
+        } else if body.contains("You gave an answer too recently") {
+            Some(Verdict::RateLimited)
+        } else if body.contains("You don't seem to be solving the right level") {
+            Some(Verdict::WrongLevel)
+        } else {
+            None
+        }
+    }
+
+    fn is_rejection(&self) -> bool {
+        matches!(self, Verdict::TooHigh | Verdict::TooLow | Verdict::Wrong)
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Verdict::Correct => "correct",
+            Verdict::TooHigh => "too_high",
+            Verdict::TooLow => "too_low",
+            Verdict::Wrong => "wrong",
+            Verdict::RateLimited => "rate_limited",
+            Verdict::WrongLevel => "wrong_level",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Verdict {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "correct" => Ok(Verdict::Correct),
+            "too_high" => Ok(Verdict::TooHigh),
+            "too_low" => Ok(Verdict::TooLow),
+            "wrong" => Ok(Verdict::Wrong),
+            "rate_limited" => Ok(Verdict::RateLimited),
+            "wrong_level" => Ok(Verdict::WrongLevel),
+            _ => Err(AocError::UnknownSymbol(s.to_string())),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Submission {
+    pub day: u8,
+    pub part: Part,
+    pub answer: Answer,
+    pub verdict: Verdict,
+}
+
+impl Display for Submission {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.day, self.part, self.verdict, self.answer
+        )
+    }
+}
+
+impl FromStr for Submission {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.splitn(4, ' ');
+        let mut next_field = || fields.next().ok_or(AocError::InvalidFormat(s.to_string()));
+        let day = next_field()?;
+        let day = u8::from_str(day).map_err(|_| AocError::UnknownSymbol(day.to_string()))?;
+        let part = next_field()?;
+        let part = Part::from_str(part).map_err(|_| AocError::UnknownSymbol(part.to_string()))?;
+        let verdict = Verdict::from_str(next_field()?)?;
+        let answer = Answer::parse(next_field()?);
+        Ok(Submission {
+            day,
+            part,
+            answer,
+            verdict,
+        })
+    }
+}
+
+/// Every answer ever posted, one `day part verdict answer` line each.
+pub struct History {
+    path: PathBuf,
+    submissions: Vec<Submission>,
+}
+
+impl History {
+    pub fn load<P>(path: P) -> AocResult<Self>
+    where
+        P: Into<PathBuf>,
+    {
+        let path = path.into();
+        let submissions = match fs::read_to_string(&path) {
+            Ok(history) => history
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(i, line)| {
+                    Submission::from_str(line).map_err(|e| AocError::at_line(i + 1, e))
+                })
+                .collect::<AocResult<_>>()?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => vec![],
+            Err(e) => return Err(e.into()),
+        };
+        Ok(History { path, submissions })
+    }
+
+    pub fn submissions(&self, day: u8, part: Part) -> impl Iterator<Item = &Submission> {
+        self.submissions
+            .iter()
+            .filter(move |submission| submission.day == day && submission.part == part)
+    }
+
+    /// Explains why `answer` is known to be wrong from earlier verdicts.
+    pub fn check(&self, day: u8, part: Part, answer: &Answer) -> Result<(), String> {
+        for submission in self.submissions(day, part) {
+            match (submission.verdict, &submission.answer, answer) {
+                (Verdict::Correct, accepted, _) => {
+                    return Err(format!("already solved with {}", accepted))
+                }
+                (verdict, rejected, _) if verdict.is_rejection() && rejected == answer => {
+                    return Err(format!("it was already rejected as {}", verdict))
+                }
+                (Verdict::TooHigh, Answer::Number(high), Answer::Number(n)) if n >= high => {
+                    return Err(format!("{} was already too high", high))
+                }
+                (Verdict::TooLow, Answer::Number(low), Answer::Number(n)) if n <= low => {
+                    return Err(format!("{} was already too low", low))
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    pub fn record(&mut self, submission: Submission) -> AocResult<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", submission)?;
+        self.submissions.push(submission);
+        Ok(())
+    }
+}
+
+pub struct Submitter<C> {
+    history: History,
+    base_url: String,
+    session: Option<String>,
+    client: C,
+}
+
+impl Submitter<UreqClient> {
+    /// Uses the `AOC_SESSION` token like `InputCache::from_env`.
+    pub fn from_env(history: History) -> Self {
+        let submitter = Submitter::new(history, UreqClient::default());
+        match std::env::var("AOC_SESSION") {
+            Ok(session) => submitter.with_session(session.trim()),
+            Err(_) => submitter,
+        }
+    }
+}
+
+impl<C> Submitter<C>
+where
+    C: HttpClient,
+{
+    pub fn new(history: History, client: C) -> Self {
+        Submitter {
+            history,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            session: None,
+            client,
+        }
+    }
+
+    pub fn with_session<S>(mut self, session: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.session = Some(session.into());
+        self
+    }
+
+    pub fn with_base_url<S>(mut self, base_url: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    /// Posts `answer` unless the history already proves it wrong, and records the verdict.
+    pub fn submit(&mut self, day: u8, part: Part, answer: Answer) -> AocResult<Verdict> {
+        if let Err(reason) = self.history.check(day, part, &answer) {
+            return Err(AocError::SubmissionRefused {
+                day,
+                part,
+                answer,
+                reason,
+            });
+        }
+        let session = self
+            .session
+            .as_deref()
+            .ok_or(AocError::MissingSession(day))?;
+        let url = format!("{}/2022/day/{}/answer", self.base_url, day);
+        let level = match part {
+            Part::A => "1",
+            Part::B => "2",
+        };
+        let answer_text = answer.to_string();
+        let response = self.client.post(
+            &url,
+            session,
+            &[("level", level), ("answer", answer_text.as_str())],
+        )?;
+        let verdict = Verdict::from_response(&response).ok_or(AocError::Http {
+            url,
+            message: "unrecognised answer page".to_string(),
+        })?;
+        self.history.record(Submission {
+            day,
+            part,
+            answer,
+            verdict,
+        })?;
+        Ok(verdict)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inputs::tests::{serve_once, temp_dir};
+
+    #[test]
+    fn history_rejects_known_wrong_answers() {
+        let dir = temp_dir("history");
+        let mut history = History::load(dir.join("submissions.txt")).unwrap();
+        let wrong = |answer: u64, verdict| Submission {
+            day: 1,
+            part: Part::A,
+            answer: Answer::Number(answer),
+            verdict,
+        };
+        history.record(wrong(900, Verdict::TooHigh)).unwrap();
+        history.record(wrong(100, Verdict::TooLow)).unwrap();
+        history.record(wrong(500, Verdict::Wrong)).unwrap();
+        let history = History::load(dir.join("submissions.txt")).unwrap();
+        assert!(history.check(1, Part::A, &Answer::Number(950)).is_err());
+        assert!(history.check(1, Part::A, &Answer::Number(100)).is_err());
+        assert!(history.check(1, Part::A, &Answer::Number(500)).is_err());
+        assert!(history.check(1, Part::A, &Answer::Number(501)).is_ok());
+        assert!(history.check(1, Part::B, &Answer::Number(950)).is_ok());
+    }
+
+    #[test]
+    fn submit_records_verdict() {
+        let (address, server) =
+            serve_once("<article><p>That's not the right answer; your answer is too high.</p>");
+        let history = History::load(temp_dir("submit").join("submissions.txt")).unwrap();
+        let mut submitter = Submitter::new(history, UreqClient::default())
+            .with_base_url(address)
+            .with_session("secret");
+        let verdict = submitter.submit(2, Part::B, Answer::Number(12000));
+        assert_eq!(verdict.unwrap(), Verdict::TooHigh);
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /2022/day/2/answer"));
+        assert!(request.ends_with("level=2&answer=12000"));
+        // Refused locally, the stand-in server is already gone.
+        assert!(matches!(
+            submitter.submit(2, Part::B, Answer::Number(13000)),
+            Err(AocError::SubmissionRefused { .. })
+        ));
+    }
+}
@@ -2,6 +2,8 @@ use std::fs::File;
 use std::io::{BufReader, Read, Result};
 use std::path::Path;
 
+use memmap2::Mmap;
+
 pub fn get_input_file<P>(path: P) -> Result<BufReader<File>>
 where
     P: AsRef<Path>,
@@ -14,8 +16,24 @@ pub fn get_input_string<P>(path: P) -> Result<String>
 where
     P: AsRef<Path>,
 {
-    let mut input_file = File::open(path)?;
+    read_input_string(File::open(path)?)
+}
+
+pub fn read_input_string<R>(mut input: R) -> Result<String>
+where
+    R: Read,
+{
     let mut result = String::new();
-    input_file.read_to_string(&mut result)?;
+    input.read_to_string(&mut result)?;
     Ok(result)
 }
+
+/// Maps the whole file into memory instead of reading it. The file must not be modified while
+/// the map is alive.
+pub fn map_input_file<P>(path: P) -> Result<Mmap>
+where
+    P: AsRef<Path>,
+{
+    let file = File::open(path)?;
+    unsafe { Mmap::map(&file) }
+}